@@ -1,3 +1,6 @@
+// This is a straight port of the C demo, which ignores the return values of the curses calls.
+#![allow(unused_must_use)]
+
 extern crate pancurses;
 extern crate rand;
 
@@ -2,10 +2,10 @@ extern crate pancurses;
 
 use pancurses::{initscr, endwin};
 
-fn main() {
+fn main() -> pancurses::Result<()> {
     let window = initscr();
-    window.printw("Hello Rust");
-    window.refresh();
+    window.printw("Hello Rust")?;
+    window.refresh()?;
     window.getch();
-    endwin();
+    endwin()
 }
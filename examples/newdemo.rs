@@ -1,3 +1,6 @@
+// This is a straight port of the C demo, which ignores the return values of the curses calls.
+#![allow(unused_must_use)]
+
 extern crate pancurses;
 extern crate rand;
 
@@ -55,7 +58,8 @@ fn main() {
         width,
         (main_window.get_max_y() - height) / 2,
         (main_window.get_max_x() - width) / 2,
-    );
+    )
+    .expect("Failed to create the drawing window");
 
     loop {
         init_pair(1, COLOR_WHITE, COLOR_BLUE);
@@ -147,7 +151,7 @@ fn main() {
             for i in (0..w + msg_len).rev() {
                 visbuf.clear();
                 for visbuf_i in 0..visbuf.capacity() {
-                    let i = i - msg_len as i32;
+                    let i = i - msg_len;
                     let char_index = visbuf_i as i32 - i;
                     let ch = if char_index >= 0 && char_index < message.len() as i32 {
                         let char_index = char_index as usize;
@@ -237,7 +241,7 @@ fn wait_for_user(main_window: &Window) -> bool {
     matches!(ch, Some(Input::Character('\x1B')))
 }
 
-fn sub_win_test(main_window: &Window, win: &Window) -> Result<(), i32> {
+fn sub_win_test(main_window: &Window, win: &Window) -> Result<()> {
     win.attrset(Attribute::Normal);
     let (h, w) = win.get_max_yx();
     let (by, bx) = win.get_beg_yx();
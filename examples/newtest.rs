@@ -1,3 +1,6 @@
+// This is a straight port of the C demo, which ignores the return values of the curses calls.
+#![allow(unused_must_use)]
+
 extern crate pancurses;
 
 use pancurses::*;
@@ -102,14 +105,14 @@ fn main() {
                 window.mv(12, 40);
                 text_in_a_box("Prev Ucode pg", &window);
             }
-            window.mvprintw(13, 40, &format!("U+{:04X} ", unicode_offset));
+            mvwprintw!(window, 13, 40, "U+{:04X} ", unicode_offset);
 
             for i in 0..128 {
                 // Show extended characters
                 window.mvaddstr(
                     5 + i % 16,
                     (i / 16) * 5,
-                    &format!("{:02X} ", i + unicode_offset),
+                    format!("{:02X} ", i + unicode_offset),
                 );
                 if i + unicode_offset > ' ' as i32 {
                     window.addch((i + unicode_offset) as chtype);
@@ -202,41 +205,35 @@ fn main() {
                 blink_state = !blink_state;
                 set_blink(blink_state);
             }
-            Some(x) if x != Input::KeyMouse => {
-                window.mvaddstr(0, COL1, &format!("Key {:?} hit          ", x));
-            }
-            Some(Input::KeyMouse) => {
-                if let Ok(mouse_event) = getmouse() {
-                    window.mvaddstr(
-                        0,
-                        COL1,
-                        &format!(
-                            "Mouse at {} x {}: {}",
-                            mouse_event.x,
-                            mouse_event.y,
-                            mouse_event.bstate
-                        ),
-                    );
-                    if mouse_event.x >= color_block_start {
-                        if mouse_event.y == 19 {
-                            // blink/non-blink toggle
-                            cursor_state_1 = (cursor_state_1 + 1) % N_CURSORS as usize;
-                        } else if mouse_event.y == 20 {
-                            // cycle cursor state
-                            cursor_state_2 = (cursor_state_2 + 1) % N_CURSORS as usize;
-                        }
-                    } else if mouse_event.x >= 40 && mouse_event.x < 40 + 10 {
-                        if mouse_event.y == 11 {
-                            redraw = true;
-                            unicode_offset += 0x80;
-                        } else if mouse_event.y == 12 && unicode_offset != 0 {
-                            redraw = true;
-                            unicode_offset -= 0x80;
-                        }
+            Some(Input::Mouse(mouse_event)) => {
+                let (y, x) = mouse_event.position;
+                window.mvaddstr(
+                    0,
+                    COL1,
+                    format!("Mouse at {} x {}: {:?}", x, y, mouse_event.kind),
+                );
+                if x >= color_block_start {
+                    if y == 19 {
+                        // blink/non-blink toggle
+                        cursor_state_1 = (cursor_state_1 + 1) % N_CURSORS as usize;
+                    } else if y == 20 {
+                        // cycle cursor state
+                        cursor_state_2 = (cursor_state_2 + 1) % N_CURSORS as usize;
+                    }
+                } else if (40..40 + 10).contains(&x) {
+                    if y == 11 {
+                        redraw = true;
+                        unicode_offset += 0x80;
+                    } else if y == 12 && unicode_offset != 0 {
+                        redraw = true;
+                        unicode_offset -= 0x80;
                     }
                 }
             }
-            _ => (),
+            Some(x) => {
+                window.mvaddstr(0, COL1, format!("Key {:?} hit          ", x));
+            }
+            None => (),
         }
     }
 
@@ -0,0 +1,100 @@
+use crate::{chtype, Error, Result};
+
+/// The maximum number of characters in a `ComplexChar`: one spacing character followed by up to
+/// four combining characters.
+pub const CCHARW_MAX: usize = 5;
+
+/// A complex character (cchar_t), the wide-character counterpart of a chtype.
+///
+/// It holds a single spacing character, optionally followed by combining characters, together with
+/// attributes and a color pair. Unlike a chtype it can represent any Unicode character, so it's
+/// used with the wide-character functions such as `Window::add_wch()` and `Window::in_wch()`.
+///
+/// On Windows PDCurses stores a single character per cell, so any combining characters are
+/// dropped when the character is added to a window.
+///
+/// ```rust
+/// use pancurses::{ComplexChar, Error, A_BOLD};
+///
+/// let e_acute = ComplexChar::new("e\u{301}", A_BOLD, 1).unwrap();
+/// assert_eq!("e\u{301}", e_acute.text());
+/// assert_eq!(A_BOLD, e_acute.attributes());
+/// assert_eq!(1, e_acute.color_pair());
+///
+/// assert_eq!(
+///     Err(Error::InvalidComplexChar {
+///         text: String::new()
+///     }),
+///     ComplexChar::new("", A_BOLD, 0)
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ComplexChar {
+    text: String,
+    attributes: chtype,
+    color_pair: i16,
+}
+
+impl ComplexChar {
+    /// Creates a complex character from a string holding a spacing character and up to four
+    /// combining characters.
+    ///
+    /// Returns `Error::InvalidComplexChar` if the string is empty, has more than `CCHARW_MAX`
+    /// characters, or contains a NUL.
+    pub fn new<T: AsRef<str>>(text: T, attributes: chtype, color_pair: i16) -> Result<ComplexChar> {
+        let text = text.as_ref();
+        let length = text.chars().count();
+        if length == 0 || length > CCHARW_MAX || text.contains('\0') {
+            return Err(Error::InvalidComplexChar {
+                text: text.to_owned(),
+            });
+        }
+        Ok(ComplexChar {
+            text: text.to_owned(),
+            attributes,
+            color_pair,
+        })
+    }
+
+    /// The spacing character and any combining characters.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The attributes of the character, excluding the color pair.
+    pub fn attributes(&self) -> chtype {
+        self.attributes
+    }
+
+    /// The color pair of the character.
+    pub fn color_pair(&self) -> i16 {
+        self.color_pair
+    }
+
+    /// Returns a copy of the character with the given attributes.
+    pub fn with_attributes(&self, attributes: chtype) -> ComplexChar {
+        ComplexChar {
+            attributes,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of the character with the given color pair.
+    pub fn with_color_pair(&self, color_pair: i16) -> ComplexChar {
+        ComplexChar {
+            color_pair,
+            ..self.clone()
+        }
+    }
+}
+
+/// A character without attributes that uses the default color pair. NUL is replaced by a space.
+impl From<char> for ComplexChar {
+    fn from(c: char) -> ComplexChar {
+        ComplexChar {
+            text: if c == '\0' { ' ' } else { c }.to_string(),
+            attributes: 0,
+            color_pair: 0,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use super::{ERR, OK};
+
+/// A specialized `Result` type for pancurses operations.
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+/// The error type returned by fallible pancurses functions.
+///
+/// Every variant records the name of the curses function that failed so that the error can be
+/// traced back to the call that caused it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The curses function returned `ERR`.
+    Curses { function: &'static str },
+    /// The curses function could not allocate or create the requested object and returned a
+    /// null pointer.
+    NullPointer { function: &'static str },
+    /// A position outside of the window was given to a function that moves the cursor.
+    OutOfBounds {
+        function: &'static str,
+        y: i32,
+        x: i32,
+    },
+    /// A string given to the function contained a NUL byte at the given byte position.
+    InteriorNul {
+        function: &'static str,
+        position: usize,
+    },
+    /// A string given to the function contained a control character at the given byte position,
+    /// and the window is set to reject them. See `ControlChars`.
+    ControlCharacter {
+        function: &'static str,
+        position: usize,
+        character: char,
+    },
+    /// The text of a `ComplexChar` was empty, contained a NUL or had more characters than fit in a
+    /// cchar_t.
+    InvalidComplexChar { text: String },
+    /// Curses was initialized while a `Session` was already alive, or a session was started after
+    /// initscr().
+    AlreadyInitialized,
+    /// The standard input or output is not connected to a terminal.
+    NotATty,
+    /// The terminal type given by `TERM` is not in the terminfo database, or `TERM` isn't set
+    /// at all (in which case the name is empty).
+    UnknownTerminal { name: String },
+    /// The terminfo database could not be found.
+    MissingTerminfo { name: String },
+    /// A window would have been given no lines or no columns, which curses takes to mean up to
+    /// the edge of the screen.
+    EmptyWindow {
+        function: &'static str,
+        nlines: i32,
+        ncols: i32,
+    },
+    /// An operating system call outside of curses failed.
+    Io {
+        function: &'static str,
+        kind: io::ErrorKind,
+    },
+}
+
+impl Error {
+    /// The name of the curses function that failed.
+    pub fn function(&self) -> &'static str {
+        match *self {
+            Error::Curses { function }
+            | Error::NullPointer { function }
+            | Error::OutOfBounds { function, .. }
+            | Error::InteriorNul { function, .. }
+            | Error::ControlCharacter { function, .. }
+            | Error::EmptyWindow { function, .. }
+            | Error::Io { function, .. } => function,
+            Error::InvalidComplexChar { .. } => "setcchar",
+            Error::AlreadyInitialized => "initscr",
+            Error::NotATty | Error::UnknownTerminal { .. } | Error::MissingTerminfo { .. } => {
+                "newterm"
+            }
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Curses { function } => write!(f, "{}() returned ERR", function),
+            Error::NullPointer { function } => write!(f, "{}() returned a null pointer", function),
+            Error::OutOfBounds { function, y, x } => write!(
+                f,
+                "{}() was given the position ({}, {}) which is outside of the window",
+                function, y, x
+            ),
+            Error::InteriorNul { function, position } => write!(
+                f,
+                "{}() was given a string with a NUL byte at position {}",
+                function, position
+            ),
+            Error::ControlCharacter {
+                function,
+                position,
+                character,
+            } => write!(
+                f,
+                "{}() was given a string with the control character {:?} at position {}",
+                function, character, position
+            ),
+            Error::InvalidComplexChar { ref text } => {
+                write!(f, "{:?} is not a valid complex character", text)
+            }
+            Error::AlreadyInitialized => write!(f, "a pancurses session is already active"),
+            Error::NotATty => write!(f, "the standard input or output is not a terminal"),
+            Error::UnknownTerminal { ref name } if name.is_empty() => {
+                write!(f, "the TERM environment variable is not set")
+            }
+            Error::UnknownTerminal { ref name } => write!(f, "unknown terminal type '{}'", name),
+            Error::MissingTerminfo { ref name } => write!(
+                f,
+                "could not find the terminfo database to look up terminal type '{}'",
+                name
+            ),
+            Error::EmptyWindow {
+                function,
+                nlines,
+                ncols,
+            } => write!(
+                f,
+                "{}() was given a size of {} by {} which leaves the window empty",
+                function, nlines, ncols
+            ),
+            Error::Io { function, kind } => write!(f, "{}() failed: {}", function, kind),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Converts an error back into the `ERR` return code used by the C API.
+impl From<Error> for i32 {
+    fn from(_: Error) -> i32 {
+        ERR
+    }
+}
+
+/// Compatibility layer for code written against the integer return codes of earlier versions.
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, ReturnCode, ERR, OK};
+///
+/// let window = initscr();
+/// assert_eq!(OK, window.mv(0, 0).code());
+/// assert_eq!(ERR, window.mv(-1, -1).code());
+/// endwin();
+/// ```
+pub trait ReturnCode {
+    /// Returns `OK` if the call succeeded and `ERR` if it failed.
+    fn code(&self) -> i32;
+}
+
+impl<T> ReturnCode for Result<T> {
+    fn code(&self) -> i32 {
+        if self.is_ok() {
+            OK
+        } else {
+            ERR
+        }
+    }
+}
+
+/// Turns a return code from a curses function into a `Result`.
+pub(crate) fn check(function: &'static str, code: i32) -> Result<()> {
+    if code == ERR {
+        Err(Error::Curses { function })
+    } else {
+        Ok(())
+    }
+}
+
+/// Turns a pointer returned by a curses function into a `Result`.
+pub(crate) fn check_ptr<T>(function: &'static str, pointer: *mut T) -> Result<*mut T> {
+    if pointer.is_null() {
+        Err(Error::NullPointer { function })
+    } else {
+        Ok(pointer)
+    }
+}
+
+/// Converts a Rust string into a C string, reporting interior NUL bytes as an error.
+pub(crate) fn to_cstring(function: &'static str, string: &str) -> Result<::std::ffi::CString> {
+    ::std::ffi::CString::new(string).map_err(|e| Error::InteriorNul {
+        function,
+        position: e.nul_position(),
+    })
+}
@@ -0,0 +1,293 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{Input, Key, Modifiers, MouseEvent};
+
+/// The markers a terminal puts around pasted text in bracketed paste mode.
+pub(crate) const PASTE_START: &str = "\x1b[200~";
+pub(crate) const PASTE_END: &str = "\x1b[201~";
+
+/// The longest control sequence getch() reads before giving up on it.
+const MAX_CSI_LENGTH: usize = 16;
+
+static ALT_DECODING: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables combining an escape character with the key that follows it into an Alt key.
+///
+/// Many terminals send Alt+x as an escape character followed by x. When this is enabled getch()
+/// waits for the escape delay after an escape character and returns the key that follows within
+/// it as `Input::KeyModified(key, Modifiers::ALT)`, or `Input::KeyEscape` if nothing follows. When
+/// it is disabled, which is the default, the escape character is returned as
+/// `Input::Character('\x1b')` right away and the key is read on its own, unless one of the
+/// terminal modes that report escape sequences is enabled, see `Input::KeyEscape`.
+///
+/// With keypad() enabled ncurses has already waited for the escape delay before returning the
+/// escape character, so there is no extra wait. Without it getch() waits for the whole escape
+/// delay after every press of the Escape key, see `set_escdelay()`.
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, set_alt_decoding, Input, Key, Modifiers};
+///
+/// let window = initscr();
+/// window.keypad(true).unwrap();
+/// let alt_x = Input::KeyModified(Key::Character('x'), Modifiers::ALT);
+/// window.ungetch(&alt_x).unwrap();
+/// assert_eq!(Some(Input::Character('\x1b')), window.getch());
+/// assert_eq!(Some(Input::Character('x')), window.getch());
+///
+/// set_alt_decoding(true);
+/// window.ungetch(&alt_x).unwrap();
+/// assert_eq!(Some(alt_x), window.getch());
+/// endwin();
+/// ```
+pub fn set_alt_decoding(enabled: bool) {
+    ALT_DECODING.store(enabled, Ordering::SeqCst);
+}
+
+pub(crate) fn alt_decoding() -> bool {
+    ALT_DECODING.load(Ordering::SeqCst)
+}
+
+/// Where the input that follows an escape character is read from.
+pub(crate) trait Source {
+    /// Reads the next input, or returns None if none arrives within the escape delay.
+    fn read(&mut self) -> Option<Input>;
+
+    /// Reads the next input, waiting for as long as it takes.
+    fn read_blocking(&mut self) -> Option<Input>;
+
+    /// Puts input back to be read again, the input put back last is read first.
+    fn unread(&mut self, input: &Input);
+}
+
+/// Decodes an escape character and the input that follows it.
+///
+/// With `alt` the next input is combined into an Alt key, and with `sequences` the control
+/// sequences of the terminal modes that curses doesn't know about are decoded. Input that isn't
+/// decoded is left in the source. Without either the escape character is returned as it is.
+pub(crate) fn decode<S: Source>(
+    input: Option<Input>,
+    source: &mut S,
+    alt: bool,
+    sequences: bool,
+) -> Option<Input> {
+    if input != Some(Input::Character('\x1b')) || !(alt || sequences) {
+        return input;
+    }
+    let next = match source.read() {
+        Some(Input::Character('[')) => return Some(decode_csi(source, alt)),
+        Some(next) => next,
+        None => return Some(Input::KeyEscape),
+    };
+    match next.with_alt() {
+        Some(input) if alt => Some(input),
+        _ => {
+            source.unread(&next);
+            Some(Input::KeyEscape)
+        }
+    }
+}
+
+/// Reads the rest of a control sequence that starts with ESC [, for the sequences curses doesn't
+/// turn into keys: bracketed paste, focus reports and SGR mouse reports.
+fn decode_csi<S: Source>(source: &mut S, alt: bool) -> Input {
+    let mut sequence = String::new();
+    let mut unread = None;
+    loop {
+        match source.read() {
+            Some(Input::Character(c))
+                if ('\x20'..='\x3f').contains(&c) && sequence.len() < MAX_CSI_LENGTH =>
+            {
+                sequence.push(c)
+            }
+            Some(Input::Character(c)) if ('\x40'..='\x7e').contains(&c) => {
+                sequence.push(c);
+                match sequence.as_str() {
+                    "200~" => return read_paste(source),
+                    "I" => return Input::FocusGained,
+                    "O" => return Input::FocusLost,
+                    _ => match MouseEvent::from_sgr(&sequence) {
+                        Some(event) => return Input::Mouse(event),
+                        None => break,
+                    },
+                }
+            }
+            other => {
+                unread = other;
+                break;
+            }
+        }
+    }
+    // Leave a sequence that isn't one of them to be read again
+    if let Some(input) = unread {
+        source.unread(&input);
+    }
+    for c in sequence.chars().rev() {
+        source.unread(&Input::Character(c));
+    }
+    if alt {
+        Input::KeyModified(Key::Character('['), Modifiers::ALT)
+    } else {
+        source.unread(&Input::Character('['));
+        Input::KeyEscape
+    }
+}
+
+/// Reads pasted text up to the marker that ends it, see `set_bracketed_paste()`.
+fn read_paste<S: Source>(source: &mut S) -> Input {
+    // The text may arrive in pieces, so wait for all of it
+    let mut text = String::new();
+    while !text.ends_with(PASTE_END) {
+        match source.read_blocking() {
+            Some(Input::Character(c)) => text.push(c),
+            Some(_) => {}
+            None => return Input::Paste(text),
+        }
+    }
+    text.truncate(text.len() - PASTE_END.len());
+    Input::Paste(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MouseButton, MouseEventKind};
+    use std::collections::VecDeque;
+
+    impl Source for VecDeque<Input> {
+        fn read(&mut self) -> Option<Input> {
+            self.pop_front()
+        }
+
+        fn read_blocking(&mut self) -> Option<Input> {
+            self.pop_front()
+        }
+
+        fn unread(&mut self, input: &Input) {
+            self.push_front(input.clone());
+        }
+    }
+
+    /// Decodes the first character of the bytes, returning the result and what is left unread.
+    fn decode_bytes(bytes: &str, alt: bool, sequences: bool) -> (Option<Input>, String) {
+        let mut source: VecDeque<Input> = bytes.chars().map(Input::Character).collect();
+        let input = source.pop_front();
+        let decoded = decode(input, &mut source, alt, sequences);
+        let rest = source
+            .into_iter()
+            .map(|input| match input {
+                Input::Character(c) => c,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        (decoded, rest)
+    }
+
+    #[test]
+    fn test_plain_input() {
+        assert_eq!(
+            (Some(Input::Character('x')), "y".to_string()),
+            decode_bytes("xy", true, true)
+        );
+        let mut source = VecDeque::new();
+        assert_eq!(None, decode(None, &mut source, true, true));
+    }
+
+    #[test]
+    fn test_escape_without_decoding() {
+        let escape = Some(Input::Character('\x1b'));
+        assert_eq!(
+            (escape.clone(), "x".to_string()),
+            decode_bytes("\x1bx", false, false)
+        );
+        assert_eq!(
+            (escape.clone(), "[I".to_string()),
+            decode_bytes("\x1b[I", false, false)
+        );
+        assert_eq!((escape, String::new()), decode_bytes("\x1b", false, false));
+    }
+
+    #[test]
+    fn test_lone_escape() {
+        assert_eq!(
+            (Some(Input::KeyEscape), String::new()),
+            decode_bytes("\x1b", true, true)
+        );
+    }
+
+    #[test]
+    fn test_alt_keys() {
+        let alt_x = Input::KeyModified(Key::Character('x'), Modifiers::ALT);
+        assert_eq!(
+            (Some(alt_x), String::new()),
+            decode_bytes("\x1bx", true, false)
+        );
+        // Without Alt decoding the key is left to be read on its own
+        assert_eq!(
+            (Some(Input::KeyEscape), "x".to_string()),
+            decode_bytes("\x1bx", false, true)
+        );
+        // Two escape characters don't make an Alt+Escape
+        assert_eq!(
+            (Some(Input::KeyEscape), "\x1b".to_string()),
+            decode_bytes("\x1b\x1b", true, true)
+        );
+    }
+
+    #[test]
+    fn test_focus_reports() {
+        assert_eq!(
+            (Some(Input::FocusGained), String::new()),
+            decode_bytes("\x1b[I", false, true)
+        );
+        assert_eq!(
+            (Some(Input::FocusLost), "x".to_string()),
+            decode_bytes("\x1b[Ox", true, true)
+        );
+    }
+
+    #[test]
+    fn test_paste() {
+        assert_eq!(
+            (Some(Input::Paste("a\nb".to_string())), "x".to_string()),
+            decode_bytes("\x1b[200~a\nb\x1b[201~x", false, true)
+        );
+        // Pasted text that is cut short is returned as far as it goes
+        assert_eq!(
+            (Some(Input::Paste("ab".to_string())), String::new()),
+            decode_bytes("\x1b[200~ab", false, true)
+        );
+    }
+
+    #[test]
+    fn test_sgr_mouse() {
+        let (decoded, rest) = decode_bytes("\x1b[<0;5;3M", false, true);
+        assert_eq!("", rest);
+        match decoded {
+            Some(Input::Mouse(event)) => {
+                assert_eq!(MouseEventKind::Pressed, event.kind);
+                assert_eq!(Some(MouseButton::Left), event.button);
+                assert_eq!((2, 4), event.position);
+            }
+            other => panic!("expected a mouse event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_sequence() {
+        // Sequences that aren't decoded are left as they were
+        let alt_bracket = Input::KeyModified(Key::Character('['), Modifiers::ALT);
+        assert_eq!(
+            (Some(alt_bracket), "1;5A".to_string()),
+            decode_bytes("\x1b[1;5A", true, true)
+        );
+        assert_eq!(
+            (Some(Input::KeyEscape), "[1;5A".to_string()),
+            decode_bytes("\x1b[1;5A", false, true)
+        );
+        // So is a sequence that is cut short
+        assert_eq!(
+            (Some(Input::KeyEscape), "[12".to_string()),
+            decode_bytes("\x1b[12", false, true)
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use std::mem;
+use std::time::{Duration, Instant};
+
+use crate::{doupdate, Input, MouseEvent, ResizeManager, Result, Session, Window};
+
+type Handler<'a, T> = Box<dyn FnMut(&mut EventContext<'a>, T) -> Result<()> + 'a>;
+type Callback<'a> = Box<dyn FnMut(&mut EventContext<'a>) -> Result<()> + 'a>;
+
+/// Identifies a timer started with `EventLoop::every()` or `EventLoop::after()`, for cancelling
+/// it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+struct Timer<'a> {
+    id: TimerId,
+    deadline: Instant,
+    /// How often the timer repeats, None for one-shot timers.
+    interval: Option<Duration>,
+    callback: Callback<'a>,
+}
+
+/// What the handlers of an `EventLoop` get to work with: the standard screen, the timers, and a
+/// way to stop the loop.
+pub struct EventContext<'a> {
+    session: Session,
+    resize_manager: ResizeManager<'a>,
+    timers: Vec<Timer<'a>>,
+    next_timer: u64,
+    /// Timers cancelled while they were running, so that they aren't started again.
+    cancelled: Vec<TimerId>,
+    quit: bool,
+}
+
+impl<'a> EventContext<'a> {
+    /// Returns the standard screen.
+    pub fn window(&self) -> &Window {
+        &self.session
+    }
+
+    /// Returns the manager that lays out windows when the terminal is resized, before the resize
+    /// handler is called.
+    pub fn resize_manager(&mut self) -> &mut ResizeManager<'a> {
+        &mut self.resize_manager
+    }
+
+    /// Stops the loop, run() returns after the handler that called this.
+    pub fn quit(&mut self) {
+        self.quit = true;
+    }
+
+    /// Calls the callback repeatedly, first after the interval.
+    pub fn every<F>(&mut self, interval: Duration, callback: F) -> TimerId
+    where
+        F: FnMut(&mut EventContext<'a>) -> Result<()> + 'a,
+    {
+        self.start_timer(interval, Some(interval), Box::new(callback))
+    }
+
+    /// Calls the callback once, after the delay.
+    pub fn after<F>(&mut self, delay: Duration, callback: F) -> TimerId
+    where
+        F: FnMut(&mut EventContext<'a>) -> Result<()> + 'a,
+    {
+        self.start_timer(delay, None, Box::new(callback))
+    }
+
+    /// Stops a timer. Cancelling a timer that has already stopped does nothing.
+    pub fn cancel(&mut self, timer: TimerId) {
+        self.timers.retain(|t| t.id != timer);
+        self.cancelled.push(timer);
+    }
+
+    fn start_timer(
+        &mut self,
+        delay: Duration,
+        interval: Option<Duration>,
+        callback: Callback<'a>,
+    ) -> TimerId {
+        let id = TimerId(self.next_timer);
+        self.next_timer += 1;
+        self.timers.push(Timer {
+            id,
+            deadline: Instant::now() + delay,
+            interval,
+            callback,
+        });
+        id
+    }
+
+    /// Runs the timers that are due, returning whether there were any.
+    fn run_timers(&mut self, now: Instant) -> Result<bool> {
+        let (due, waiting): (Vec<_>, Vec<_>) = mem::take(&mut self.timers)
+            .into_iter()
+            .partition(|t| t.deadline <= now);
+        self.timers = waiting;
+        self.cancelled.clear();
+        let ran = !due.is_empty();
+        let mut due = due.into_iter();
+        while let Some(mut timer) = due.next() {
+            if self.cancelled.contains(&timer.id) {
+                continue;
+            }
+            if self.quit {
+                // Leave the rest for when the loop runs again
+                self.timers.push(timer);
+                continue;
+            }
+            let result = (timer.callback)(self);
+            if let Some(interval) = timer.interval {
+                if !self.cancelled.contains(&timer.id) {
+                    // Keep to the schedule, but don't try to catch up on the calls that were missed
+                    timer.deadline = (timer.deadline + interval).max(now);
+                    self.timers.push(timer);
+                }
+            }
+            if result.is_err() {
+                // The timers that didn't get to run are still due when the loop runs again
+                for timer in due {
+                    if !self.cancelled.contains(&timer.id) {
+                        self.timers.push(timer);
+                    }
+                }
+                return result.map(|_| ran);
+            }
+        }
+        Ok(ran)
+    }
+}
+
+/// Runs a program: reads the input, dispatches it to handlers, runs timers and updates the screen.
+///
+/// The loop owns the `Session`, so the terminal is restored when the loop is dropped, also after
+/// a handler returned an error or panicked. Keypad mode is enabled on the standard screen.
+///
+/// Each time around the loop, getch() waits for input until the next timer is due. The handler
+/// for the input or the timers are called, and then the screen is updated with doupdate() once,
+/// after calling the draw handler and noutrefresh() on the standard screen. Other windows that
+/// are drawn to should be marked with noutrefresh() as well. `set_frame_rate()` limits how often
+/// the screen is updated, so that plenty of input or timers don't cause flicker.
+///
+/// ```rust
+/// use pancurses::{EventLoop, Input};
+/// use std::time::Duration;
+///
+/// let mut event_loop = EventLoop::new().unwrap();
+/// event_loop.set_frame_rate(30);
+/// event_loop.on_input(|context, input| {
+///     if input == Input::Character('q') {
+///         context.quit();
+///     }
+///     Ok(())
+/// });
+/// let mut frame = 0;
+/// event_loop.every(Duration::from_millis(10), move |context| {
+///     frame += 1;
+///     context.window().mvprintw(0, 0, format!("Frame {}", frame))?;
+///     if frame == 5 {
+///         context.quit();
+///     }
+///     Ok(())
+/// });
+/// event_loop.run().unwrap();
+/// ```
+pub struct EventLoop<'a> {
+    context: EventContext<'a>,
+    on_input: Option<Handler<'a, Input>>,
+    on_mouse: Option<Handler<'a, MouseEvent>>,
+    on_resize: Option<Callback<'a>>,
+    on_draw: Option<Callback<'a>>,
+    frame_interval: Duration,
+}
+
+impl<'a> EventLoop<'a> {
+    /// Starts a `Session` and creates a loop for it.
+    pub fn new() -> Result<EventLoop<'a>> {
+        Session::new().and_then(EventLoop::with_session)
+    }
+
+    /// Creates a loop for a session that has already been started, e.g. with try_initscr().
+    pub fn with_session(session: Session) -> Result<EventLoop<'a>> {
+        session.keypad(true)?;
+        Ok(EventLoop {
+            context: EventContext {
+                session,
+                resize_manager: ResizeManager::new(),
+                timers: Vec::new(),
+                next_timer: 0,
+                cancelled: Vec::new(),
+                quit: false,
+            },
+            on_input: None,
+            on_mouse: None,
+            on_resize: None,
+            on_draw: None,
+            frame_interval: Duration::from_secs(0),
+        })
+    }
+
+    /// Returns the standard screen.
+    pub fn window(&self) -> &Window {
+        self.context.window()
+    }
+
+    /// Returns the manager that lays out windows when the terminal is resized, before the resize
+    /// handler is called.
+    ///
+    /// The windows can borrow from anything that outlives the loop, such as subwindows of a window
+    /// created before it.
+    ///
+    /// ```rust
+    /// use pancurses::{newwin, EventLoop, Session};
+    /// use std::rc::Rc;
+    ///
+    /// let session = Session::new().unwrap();
+    /// let frame = Rc::new(newwin(10, 40, 0, 0).unwrap());
+    /// let mut event_loop = EventLoop::with_session(session).unwrap();
+    /// // Keep the frame across the top half of the screen, and the body inside its border
+    /// let body = Rc::new(frame.derwin(8, 38, 1, 1).unwrap());
+    /// let manager = event_loop.resize_manager();
+    /// manager.register(&frame, |lines, cols| (lines / 2, cols, 0, 0));
+    /// manager.register(&body, |lines, cols| (lines / 2 - 2, cols - 2, 1, 1));
+    /// ```
+    pub fn resize_manager(&mut self) -> &mut ResizeManager<'a> {
+        self.context.resize_manager()
+    }
+
+    /// Sets the handler for keys and the other input that isn't for the mouse or resizing.
+    pub fn on_input<F>(&mut self, handler: F)
+    where
+        F: FnMut(&mut EventContext<'a>, Input) -> Result<()> + 'a,
+    {
+        self.on_input = Some(Box::new(handler));
+    }
+
+    /// Sets the handler for mouse events, which are only reported once enabled with mousemask()
+    /// or `set_mouse_tracking()`.
+    pub fn on_mouse<F>(&mut self, handler: F)
+    where
+        F: FnMut(&mut EventContext<'a>, MouseEvent) -> Result<()> + 'a,
+    {
+        self.on_mouse = Some(Box::new(handler));
+    }
+
+    /// Sets the handler that is called after the terminal was resized, once curses has adjusted
+    /// the standard screen and the resize manager the windows registered with it to the new size.
+    pub fn on_resize<F>(&mut self, handler: F)
+    where
+        F: FnMut(&mut EventContext<'a>) -> Result<()> + 'a,
+    {
+        self.on_resize = Some(Box::new(handler));
+    }
+
+    /// Sets the handler that is called before each update of the screen.
+    pub fn on_draw<F>(&mut self, handler: F)
+    where
+        F: FnMut(&mut EventContext<'a>) -> Result<()> + 'a,
+    {
+        self.on_draw = Some(Box::new(handler));
+    }
+
+    /// Limits how many times a second the screen is updated, 0 for no limit, which is the default.
+    pub fn set_frame_rate(&mut self, frames_per_second: u32) {
+        self.frame_interval = if frames_per_second == 0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs(1) / frames_per_second
+        };
+    }
+
+    /// Calls the callback repeatedly, first after the interval.
+    pub fn every<F>(&mut self, interval: Duration, callback: F) -> TimerId
+    where
+        F: FnMut(&mut EventContext<'a>) -> Result<()> + 'a,
+    {
+        self.context.every(interval, callback)
+    }
+
+    /// Calls the callback once, after the delay.
+    pub fn after<F>(&mut self, delay: Duration, callback: F) -> TimerId
+    where
+        F: FnMut(&mut EventContext<'a>) -> Result<()> + 'a,
+    {
+        self.context.after(delay, callback)
+    }
+
+    /// Stops a timer.
+    pub fn cancel(&mut self, timer: TimerId) {
+        self.context.cancel(timer)
+    }
+
+    /// Runs the loop until a handler calls `EventContext::quit()` or returns an error.
+    ///
+    /// The loop can be run again afterwards, with its timers where they were left. That includes
+    /// a timer that returned an error, if it repeats, and the timers that were due after it.
+    ///
+    /// ```rust
+    /// use pancurses::{Error, EventLoop};
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    /// use std::time::Duration;
+    ///
+    /// let mut event_loop = EventLoop::new().unwrap();
+    /// let ticks = Rc::new(Cell::new(0));
+    /// let counter = ticks.clone();
+    /// event_loop.every(Duration::from_millis(1), move |context| {
+    ///     counter.set(counter.get() + 1);
+    ///     if counter.get() == 1 {
+    ///         return Err(Error::Curses { function: "tick" });
+    ///     }
+    ///     context.quit();
+    ///     Ok(())
+    /// });
+    /// assert_eq!(Err(Error::Curses { function: "tick" }), event_loop.run());
+    /// event_loop.run().unwrap();
+    /// assert_eq!(2, ticks.get());
+    /// ```
+    pub fn run(&mut self) -> Result<()> {
+        self.context.quit = false;
+        let mut last_frame: Option<Instant> = None;
+        let mut changed = true;
+        while !self.context.quit {
+            let now = Instant::now();
+            let next_frame = match last_frame {
+                Some(last_frame) if changed => Some(last_frame + self.frame_interval),
+                None if changed => Some(now),
+                _ => None,
+            };
+            let next_timer = self.context.timers.iter().map(|t| t.deadline).min();
+            let wake_up = match (next_frame, next_timer) {
+                (Some(frame), Some(timer)) => Some(frame.min(timer)),
+                (frame, timer) => frame.or(timer),
+            };
+            self.context
+                .window()
+                .timeout(wake_up.map_or(-1, |wake_up| milliseconds_until(now, wake_up)));
+
+            if let Some(input) = self.context.window().getch() {
+                self.dispatch(input)?;
+                changed = true;
+            }
+            if self.context.run_timers(Instant::now())? {
+                changed = true;
+            }
+
+            let now = Instant::now();
+            let frame_due = match last_frame {
+                Some(last_frame) => now >= last_frame + self.frame_interval,
+                None => true,
+            };
+            if changed && frame_due && !self.context.quit {
+                if let Some(ref mut on_draw) = self.on_draw {
+                    on_draw(&mut self.context)?;
+                }
+                self.context.window().noutrefresh()?;
+                doupdate()?;
+                last_frame = Some(now);
+                changed = false;
+            }
+        }
+        Ok(())
+    }
+
+    fn dispatch(&mut self, input: Input) -> Result<()> {
+        let context = &mut self.context;
+        match input {
+            Input::KeyResize => {
+                // Windows that don't fit on a screen this small are left where they were
+                let _ = context.resize_manager.resize();
+                match self.on_resize {
+                    Some(ref mut on_resize) => on_resize(context),
+                    None => Ok(()),
+                }
+            }
+            Input::Mouse(event) => match self.on_mouse {
+                Some(ref mut on_mouse) => on_mouse(context, event),
+                None => Ok(()),
+            },
+            input => match self.on_input {
+                Some(ref mut on_input) => on_input(context, input),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+/// Returns the time from now until then for timeout(), rounded up so that the wait doesn't end
+/// just before.
+fn milliseconds_until(now: Instant, then: Instant) -> i32 {
+    let wait = then.saturating_duration_since(now);
+    let milliseconds = wait.as_secs() * 1000 + u64::from(wait.subsec_nanos().div_ceil(1_000_000));
+    milliseconds.min(i32::MAX as u64) as i32
+}
@@ -0,0 +1,695 @@
+use std::fmt;
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+use crate::line_editor::pasted_chars;
+use crate::width::char_width;
+use crate::{display_width, Input, Result, Window};
+use crate::{A_BOLD, A_UNDERLINE};
+
+/// A function that checks the value of a field, returning the message to show if it's invalid.
+pub type Validator = Box<dyn Fn(&str) -> ::std::result::Result<(), String>>;
+
+enum Kind {
+    Text,
+    Integer {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    Choice {
+        options: Vec<String>,
+        index: usize,
+    },
+    #[cfg(feature = "regex")]
+    Regex(Regex),
+}
+
+/// A labeled input field of a `Form`.
+///
+/// The kind of field decides which characters can be typed into it and how its value is
+/// validated. A field is validated when the focus leaves it and when the form is submitted.
+pub struct Field {
+    label: String,
+    value: String,
+    kind: Kind,
+    width: usize,
+    masked: bool,
+    required: bool,
+    new_page: bool,
+    validator: Option<Validator>,
+    error: Option<String>,
+    cursor: usize,
+}
+
+impl Field {
+    fn new(label: &str, width: usize, kind: Kind) -> Field {
+        Field {
+            label: label.to_owned(),
+            value: String::new(),
+            kind,
+            width,
+            masked: false,
+            required: false,
+            new_page: false,
+            validator: None,
+            error: None,
+            cursor: 0,
+        }
+    }
+
+    /// A field that accepts any text, shown in width columns.
+    pub fn text(label: &str, width: usize) -> Field {
+        Field::new(label, width, Kind::Text)
+    }
+
+    /// A text field that shows an asterisk in place of each character typed into it.
+    pub fn password(label: &str, width: usize) -> Field {
+        Field::text(label, width).masked(true)
+    }
+
+    /// A field that accepts a whole number, optionally within the given inclusive bounds.
+    pub fn integer(label: &str, width: usize, min: Option<i64>, max: Option<i64>) -> Field {
+        Field::new(label, width, Kind::Integer { min, max })
+    }
+
+    /// A field whose value is one of the given options, changed with the left and right arrow
+    /// keys or space. The first option is chosen initially.
+    pub fn choice<T: Into<String>>(label: &str, options: Vec<T>) -> Field {
+        let options: Vec<String> = options.into_iter().map(Into::into).collect();
+        let width = options.iter().map(|o| display_width(o)).max().unwrap_or(0);
+        let mut field = Field::new(label, width, Kind::Choice { options, index: 0 });
+        field.sync_choice();
+        field
+    }
+
+    /// A field whose whole value must match the regular expression.
+    ///
+    /// The expression is anchored at both ends of the value, so that any of its alternatives can
+    /// match the whole of it. Flags set with a `RegexBuilder` are lost in the process, use inline
+    /// flags such as `(?i)` instead.
+    ///
+    /// ```rust
+    /// extern crate pancurses;
+    /// extern crate regex;
+    ///
+    /// use pancurses::{Field, Form, FormEvent, Input};
+    /// use regex::Regex;
+    ///
+    /// fn main() {
+    ///     let mut form = Form::new(vec![Field::regex("Code", 8, Regex::new("[A-Z]{3}").unwrap())]);
+    ///     for c in "ABCD".chars() {
+    ///         form.handle_input(&Input::Character(c));
+    ///     }
+    ///     assert_eq!(Some(FormEvent::Invalid(0)), form.handle_input(&Input::Character('\n')));
+    ///     form.handle_input(&Input::KeyBackspace);
+    ///     assert_eq!(Some(FormEvent::Submitted), form.handle_input(&Input::Character('\n')));
+    /// }
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn regex(label: &str, width: usize, regex: Regex) -> Field {
+        let anchored = Regex::new(&format!(r"\A(?:{})\z", regex.as_str()))
+            .expect("wrapping a valid regular expression in a group keeps it valid");
+        Field::new(label, width, Kind::Regex(anchored))
+    }
+
+    /// Sets the initial value of the field. For a choice field the value is chosen if it's one of
+    /// the options.
+    pub fn with_value<T: Into<String>>(mut self, value: T) -> Field {
+        self.set_value(value);
+        self
+    }
+
+    /// Sets whether the characters of the field are shown as asterisks.
+    pub fn masked(mut self, masked: bool) -> Field {
+        self.masked = masked;
+        self
+    }
+
+    /// Sets whether the field must have a value.
+    pub fn required(mut self, required: bool) -> Field {
+        self.required = required;
+        self
+    }
+
+    /// Makes the field the first field of a new page of the form.
+    pub fn new_page(mut self) -> Field {
+        self.new_page = true;
+        self
+    }
+
+    /// Adds a check that is run after the built in validation of the field.
+    pub fn validator<F>(mut self, validator: F) -> Field
+    where
+        F: Fn(&str) -> ::std::result::Result<(), String> + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// The label shown in front of the field.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The current value of the field.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Replaces the value of the field and clears its validation error.
+    pub fn set_value<T: Into<String>>(&mut self, value: T) {
+        let value = value.into();
+        if let Kind::Choice {
+            ref options,
+            ref mut index,
+        } = self.kind
+        {
+            if let Some(position) = options.iter().position(|o| *o == value) {
+                *index = position;
+            }
+        } else {
+            self.value = value;
+        }
+        self.sync_choice();
+        self.cursor = self.value.chars().count();
+        self.error = None;
+    }
+
+    /// The message explaining why the value of the field is invalid, if it was found to be invalid
+    /// when it was last validated.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Validates the value of the field, remembering the error. Returns true if it's valid.
+    fn validate(&mut self) -> bool {
+        self.error = self.check().err();
+        self.error.is_none()
+    }
+
+    fn check(&self) -> ::std::result::Result<(), String> {
+        if self.value.is_empty() {
+            return if self.required {
+                Err("a value is required".to_owned())
+            } else {
+                Ok(())
+            };
+        }
+        match self.kind {
+            Kind::Text | Kind::Choice { .. } => {}
+            Kind::Integer { min, max } => {
+                let number: i64 = self
+                    .value
+                    .parse()
+                    .map_err(|_| "must be a whole number".to_owned())?;
+                if let Some(min) = min.filter(|&min| number < min) {
+                    return Err(format!("must be at least {}", min));
+                }
+                if let Some(max) = max.filter(|&max| number > max) {
+                    return Err(format!("must be at most {}", max));
+                }
+            }
+            #[cfg(feature = "regex")]
+            Kind::Regex(ref regex) => {
+                if !regex.is_match(&self.value) {
+                    return Err("is not in the expected format".to_owned());
+                }
+            }
+        }
+        match self.validator {
+            Some(ref validator) => validator(&self.value),
+            None => Ok(()),
+        }
+    }
+
+    fn sync_choice(&mut self) {
+        if let Kind::Choice { ref options, index } = self.kind {
+            self.value = options.get(index).cloned().unwrap_or_default();
+        }
+    }
+
+    /// Handles input that edits the value of the field, returning true if the value changed.
+    fn edit(&mut self, input: &Input) -> bool {
+        if let Kind::Choice {
+            ref options,
+            ref mut index,
+        } = self.kind
+        {
+            if options.is_empty() {
+                return false;
+            }
+            match *input {
+                Input::KeyLeft => *index = (*index + options.len() - 1) % options.len(),
+                Input::KeyRight | Input::Character(' ') => *index = (*index + 1) % options.len(),
+                _ => return false,
+            }
+            self.sync_choice();
+            return true;
+        }
+
+        let length = self.value.chars().count();
+        match *input {
+            Input::KeyLeft => self.cursor = self.cursor.saturating_sub(1),
+            Input::KeyRight => self.cursor = (self.cursor + 1).min(length),
+            Input::KeyHome => self.cursor = 0,
+            Input::KeyEnd => self.cursor = length,
+            Input::KeyBackspace | Input::Character('\x08') | Input::Character('\x7f') => {
+                if self.cursor == 0 {
+                    return false;
+                }
+                self.cursor -= 1;
+                self.remove_at(self.cursor);
+                return true;
+            }
+            Input::KeyDC => {
+                if self.cursor == length {
+                    return false;
+                }
+                self.remove_at(self.cursor);
+                return true;
+            }
+            Input::Character(c) if !c.is_control() && self.accepts(c) => {
+                self.insert(c);
+                return true;
+            }
+            Input::Paste(ref text) => {
+                let mut changed = false;
+                for c in pasted_chars(text) {
+                    if self.accepts(c) {
+                        self.insert(c);
+                        changed = true;
+                    }
+                }
+                return changed;
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Returns false for characters that can never be part of a valid value.
+    fn accepts(&self, c: char) -> bool {
+        match self.kind {
+            Kind::Integer { .. } => c.is_ascii_digit() || (c == '-' && self.cursor == 0),
+            _ => true,
+        }
+    }
+
+    fn insert(&mut self, c: char) {
+        let offset = self.byte_offset(self.cursor);
+        self.value.insert(offset, c);
+        self.cursor += 1;
+    }
+
+    fn byte_offset(&self, index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(index)
+            .map_or(self.value.len(), |(offset, _)| offset)
+    }
+
+    fn remove_at(&mut self, index: usize) {
+        let offset = self.byte_offset(index);
+        self.value.remove(offset);
+    }
+
+    /// The text to show in the field, scrolled so that the cursor is visible if the field has the
+    /// focus, and the column of the cursor within it.
+    fn visible_text(&self, focused: bool) -> (String, usize) {
+        let chars: Vec<char> = if self.masked {
+            self.value.chars().map(|_| '*').collect()
+        } else {
+            self.value.chars().collect()
+        };
+        let width_of = |chars: &[char]| -> usize { chars.iter().cloned().map(char_width).sum() };
+        let mut start = 0;
+        while focused && start < self.cursor && width_of(&chars[start..self.cursor]) >= self.width {
+            start += 1;
+        }
+        let text: String = chars[start..].iter().collect();
+        (text, width_of(&chars[start..self.cursor]))
+    }
+}
+
+impl fmt::Debug for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Field")
+            .field("label", &self.label)
+            .field("value", &if self.masked { "***" } else { &self.value })
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+/// What happened to a `Form` as a result of the input it was given.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FormEvent {
+    /// The value of the field with the given index was changed.
+    Edited(usize),
+    /// The focus moved to the field with the given index.
+    FocusChanged(usize),
+    /// The form moved to the page with the given index.
+    PageChanged(usize),
+    /// The field with the given index is invalid, so the focus couldn't leave it or the form
+    /// couldn't be submitted. The message is available from `Field::error()`.
+    Invalid(usize),
+    /// Enter was pressed and all of the fields are valid.
+    Submitted,
+}
+
+/// A form of labeled fields that is rendered into a window and driven by `Input`.
+///
+/// Each field is drawn on its own line with its label in front of it. Fields can be split into
+/// pages with `Field::new_page()`, and only the fields of the current page are drawn. The form is
+/// implemented in Rust on top of `Window`, so it works the same way on all platforms.
+///
+/// handle_input() understands the following input:
+///
+/// - Tab and the down arrow move the focus to the next field, and Shift-Tab (`KeySTab` or
+///   `KeyBTab`, depending on the terminal) and the up arrow to the previous one. The focus only leaves a field if its value is valid.
+/// - Page Up and Page Down move to the previous or next page.
+/// - Enter validates every field and submits the form if they're all valid.
+/// - Everything else edits the field with the focus. Pasted text is inserted at the cursor, with
+///   line breaks turned into spaces and characters the field doesn't accept left out.
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, Field, Form, FormEvent, Input};
+///
+/// let window = initscr();
+/// let mut form = Form::new(vec![
+///     Field::text("Name", 20).required(true),
+///     Field::integer("Age", 3, Some(0), Some(150)),
+///     Field::choice("Color", vec!["Red", "Green", "Blue"]),
+///     Field::password("Password", 20).new_page(),
+/// ]);
+///
+/// assert_eq!(Some(FormEvent::Invalid(0)), form.handle_input(&Input::Character('\t')));
+/// assert_eq!(Some("a value is required"), form.fields()[0].error());
+/// for c in "Ferris".chars() {
+///     form.handle_input(&Input::Character(c));
+/// }
+/// assert_eq!(Some(FormEvent::FocusChanged(1)), form.handle_input(&Input::Character('\t')));
+/// for c in "200".chars() {
+///     form.handle_input(&Input::Character(c));
+/// }
+/// assert_eq!(Some(FormEvent::Invalid(1)), form.handle_input(&Input::Character('\n')));
+/// assert_eq!(Some("must be at most 150"), form.fields()[1].error());
+/// form.handle_input(&Input::KeyBackspace);
+///
+/// assert_eq!(Some(FormEvent::FocusChanged(2)), form.handle_input(&Input::KeyDown));
+/// assert_eq!(Some(FormEvent::Edited(2)), form.handle_input(&Input::KeyRight));
+/// assert_eq!("Green", form.fields()[2].value());
+/// assert_eq!(Some(FormEvent::PageChanged(1)), form.handle_input(&Input::KeyNPage));
+/// assert_eq!(Some(FormEvent::Submitted), form.handle_input(&Input::Character('\n')));
+///
+/// form.draw(&window).unwrap();
+/// window.refresh().unwrap();
+/// endwin();
+/// ```
+#[derive(Debug)]
+pub struct Form {
+    fields: Vec<Field>,
+    current: usize,
+}
+
+impl Form {
+    /// Creates a form with the focus on the first field.
+    pub fn new(fields: Vec<Field>) -> Form {
+        Form { fields, current: 0 }
+    }
+
+    /// The fields of the form.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// The field with the given index, for changing its value.
+    pub fn field_mut(&mut self, index: usize) -> Option<&mut Field> {
+        self.fields.get_mut(index)
+    }
+
+    /// The index of the field with the focus.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Moves the focus to the field with the given index, without validating the current field.
+    pub fn set_current(&mut self, index: usize) {
+        if index < self.fields.len() {
+            self.current = index;
+        }
+    }
+
+    /// The index of the page that is shown.
+    pub fn page(&self) -> usize {
+        self.page_of(self.current)
+    }
+
+    /// The number of pages in the form.
+    pub fn page_count(&self) -> usize {
+        if self.fields.is_empty() {
+            0
+        } else {
+            self.page_of(self.fields.len() - 1) + 1
+        }
+    }
+
+    /// Validates every field. Returns the index of the first invalid field if there is one.
+    pub fn validate(&mut self) -> Option<usize> {
+        let mut first_invalid = None;
+        for (index, field) in self.fields.iter_mut().enumerate() {
+            if !field.validate() && first_invalid.is_none() {
+                first_invalid = Some(index);
+            }
+        }
+        first_invalid
+    }
+
+    /// Draws the current page of the form into the window, starting from its upper left corner,
+    /// and leaves the cursor in the field with the focus.
+    pub fn draw(&self, window: &Window) -> Result<()> {
+        window.erase()?;
+        let page = self.page();
+        let fields: Vec<usize> = (0..self.fields.len())
+            .filter(|&index| self.page_of(index) == page)
+            .collect();
+        let label_width = fields
+            .iter()
+            .map(|&index| display_width(&self.fields[index].label))
+            .max()
+            .unwrap_or(0);
+        let columns = window.get_max_x().max(0) as usize;
+
+        let mut cursor = (0, 0);
+        for (row, &index) in fields.iter().enumerate() {
+            let row = row as i32;
+            if row >= window.get_max_y() {
+                break;
+            }
+            let field = &self.fields[index];
+            if index == self.current {
+                window.attron(A_BOLD)?;
+            }
+            let used = window.mvaddstr_cols(row, 0, &field.label, columns, None)?;
+            window.attroff(A_BOLD)?;
+            let start = label_width + 2;
+            if start >= columns {
+                continue;
+            }
+            window.addstr(" ".repeat(start - used))?;
+
+            let (text, cursor_column) = field.visible_text(index == self.current);
+            let width = field.width.min(columns - start);
+            window.attron(A_UNDERLINE)?;
+            let written = window.addstr_cols(&text, width, None)?;
+            window.addstr(" ".repeat(width - written))?;
+            window.attroff(A_UNDERLINE)?;
+            if let Some(ref error) = field.error {
+                let left = columns.saturating_sub(start + width + 1);
+                if left > 0 {
+                    window.attron(A_BOLD)?;
+                    window.addstr_cols(format!(" {}", error), left, Some("…"))?;
+                    window.attroff(A_BOLD)?;
+                }
+            }
+            if index == self.current {
+                cursor = (row, (start + cursor_column.min(width)) as i32);
+            }
+        }
+
+        let pages = self.page_count();
+        if pages > 1 {
+            let footer = format!("Page {}/{}", page + 1, pages);
+            window.mvaddstr_cols(
+                window.get_max_y() - 1,
+                0,
+                footer,
+                columns.saturating_sub(1),
+                None,
+            )?;
+        }
+        window.mv(cursor.0, cursor.1)
+    }
+
+    /// Moves between the fields, edits the current one or submits the form. Input the form has
+    /// no use for gives None.
+    pub fn handle_input(&mut self, input: &Input) -> Option<FormEvent> {
+        if self.fields.is_empty() {
+            return None;
+        }
+        match *input {
+            Input::Character('\t') | Input::KeyDown => {
+                let next = (self.current + 1) % self.fields.len();
+                self.focus(next)
+            }
+            Input::KeySTab | Input::KeyBTab | Input::KeyUp => {
+                let previous = (self.current + self.fields.len() - 1) % self.fields.len();
+                self.focus(previous)
+            }
+            Input::KeyNPage => {
+                let page = self.page();
+                let next = (self.current..self.fields.len()).find(|&i| self.page_of(i) > page);
+                next.and_then(|index| self.focus(index))
+            }
+            Input::KeyPPage => {
+                let page = self.page();
+                if page == 0 {
+                    return None;
+                }
+                let previous = (0..self.fields.len()).find(|&i| self.page_of(i) == page - 1);
+                previous.and_then(|index| self.focus(index))
+            }
+            Input::Character('\n') | Input::Character('\r') | Input::KeyEnter => {
+                match self.validate() {
+                    Some(index) => {
+                        self.current = index;
+                        Some(FormEvent::Invalid(index))
+                    }
+                    None => Some(FormEvent::Submitted),
+                }
+            }
+            _ => {
+                let current = self.current;
+                let field = &mut self.fields[current];
+                if field.edit(input) {
+                    field.error = None;
+                    Some(FormEvent::Edited(current))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Moves the focus to the field if the current field is valid.
+    fn focus(&mut self, index: usize) -> Option<FormEvent> {
+        if !self.fields[self.current].validate() {
+            return Some(FormEvent::Invalid(self.current));
+        }
+        if index == self.current {
+            return None;
+        }
+        let page = self.page();
+        self.current = index;
+        if self.page() != page {
+            Some(FormEvent::PageChanged(self.page()))
+        } else {
+            Some(FormEvent::FocusChanged(index))
+        }
+    }
+
+    /// The page of the field with the given index, counting the page breaks before it. A break
+    /// in front of the first field doesn't start a page, as there's nothing before it.
+    fn page_of(&self, index: usize) -> usize {
+        self.fields
+            .iter()
+            .take(index + 1)
+            .skip(1)
+            .filter(|f| f.new_page)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_form() {
+        let mut form = Form::new(vec![]);
+        assert_eq!(0, form.page());
+        assert_eq!(0, form.page_count());
+        assert_eq!(None, form.handle_input(&Input::Character('\t')));
+        assert_eq!(None, form.handle_input(&Input::KeyNPage));
+        assert_eq!(None, form.handle_input(&Input::Character('x')));
+    }
+
+    #[test]
+    fn test_page_count() {
+        let form = Form::new(vec![Field::text("One", 5).new_page()]);
+        assert_eq!(1, form.page_count());
+
+        let mut form = Form::new(vec![
+            Field::text("One", 5),
+            Field::text("Two", 5),
+            Field::text("Three", 5).new_page(),
+            Field::text("Four", 5).new_page(),
+            Field::text("Five", 5),
+        ]);
+        assert_eq!(3, form.page_count());
+        assert_eq!(0, form.page());
+        assert_eq!(
+            Some(FormEvent::PageChanged(1)),
+            form.handle_input(&Input::KeyNPage)
+        );
+        assert_eq!(2, form.current());
+        assert_eq!(
+            Some(FormEvent::PageChanged(2)),
+            form.handle_input(&Input::KeyNPage)
+        );
+        assert_eq!(None, form.handle_input(&Input::KeyNPage));
+        assert_eq!(
+            Some(FormEvent::FocusChanged(4)),
+            form.handle_input(&Input::KeyDown)
+        );
+        assert_eq!(
+            Some(FormEvent::PageChanged(1)),
+            form.handle_input(&Input::KeyPPage)
+        );
+        assert_eq!(2, form.current());
+    }
+
+    #[test]
+    fn test_shift_tab_moves_back() {
+        let mut form = Form::new(vec![Field::text("One", 5), Field::text("Two", 5)]);
+        form.set_current(1);
+        assert_eq!(
+            Some(FormEvent::FocusChanged(0)),
+            form.handle_input(&Input::KeySTab)
+        );
+        assert_eq!(
+            Some(FormEvent::FocusChanged(1)),
+            form.handle_input(&Input::KeyBTab)
+        );
+        assert_eq!(
+            Some(FormEvent::FocusChanged(0)),
+            form.handle_input(&Input::Character('\t'))
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_regex_alternation() {
+        let regex = Regex::new("[A-Z]{3}|[A-Z]{4}").unwrap();
+        let mut field = Field::regex("Code", 8, regex);
+        for value in &["ABC", "ABCD"] {
+            field.set_value(*value);
+            assert_eq!(Ok(()), field.check());
+        }
+        for value in &["AB", "ABCDE", "xABC", "ABC "] {
+            field.set_value(*value);
+            assert!(field.check().is_err(), "{:?} was accepted", value);
+        }
+    }
+}
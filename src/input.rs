@@ -1,4 +1,8 @@
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+use std::ops::{BitOr, BitOrAssign};
+
+use crate::MouseEvent;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Input {
     Character(char),
     Unknown(i32),
@@ -118,4 +122,180 @@ pub enum Input {
     KeyB2,
     KeyC1,
     KeyC3,
+
+    /// A key pressed together with modifier keys, such as Ctrl+Left or Alt+Shift+F5.
+    ///
+    /// On ncurses these are the keys terminfo describes with extended names like kUP5 (Ctrl+Up)
+    /// or kLFT3 (Alt+Left), and the function keys from F13 on whose escape sequences carry
+    /// modifiers. Those are reported as the key from F1 to F12 they are based on, so Shift+F1,
+    /// which xterm numbers F13, is `KeyModified(Key::F(1), Modifiers::SHIFT)`. F13 to F15 are
+    /// reported as KeyF13 to KeyF15 only on terminals where they are keys of their own, and the
+    /// function keys above F15 that are keys of their own as `KeyModified(Key::F(n),
+    /// Modifiers::NONE)`. Keys with just Shift that curses has codes of its own for are still
+    /// reported with those, such as KeySLeft. On PDCurses these are its CTL_ and ALT_ key codes.
+    KeyModified(Key, Modifiers),
+
+    /// The Escape key on its own, as opposed to the start of an escape sequence or an Alt key.
+    ///
+    /// This is only returned while getch() decodes what follows escape characters, that is with
+    /// `set_alt_decoding()` or one of the terminal modes that report escape sequences enabled,
+    /// such as `set_bracketed_paste()`. getch() then waits for the escape delay after reading an
+    /// escape character and returns this if nothing that it decodes follows. Otherwise the
+    /// escape character is returned as `Character('\x1b')`, so code that should react to the
+    /// Escape key either way matches both.
+    KeyEscape,
+
+    /// Text pasted into the terminal while bracketed paste is enabled, see
+    /// `set_bracketed_paste()`.
+    Paste(String),
+
+    /// The terminal window gained the focus, while focus reporting is enabled, see
+    /// `set_focus_reporting()`.
+    FocusGained,
+    /// The terminal window lost the focus, while focus reporting is enabled.
+    FocusLost,
+
+    /// A mouse event, which getch() reads with getmouse() when curses reports KeyMouse.
+    Mouse(MouseEvent),
+
+    /// Another thread called `Waker::wake()` while getch() waited for input.
+    Wakeup,
+}
+
+/// The inputs for the keys that curses reports without modifiers.
+const PLAIN_KEYS: [(Input, Key); 22] = [
+    (Input::KeyUp, Key::Up),
+    (Input::KeyDown, Key::Down),
+    (Input::KeyLeft, Key::Left),
+    (Input::KeyRight, Key::Right),
+    (Input::KeyHome, Key::Home),
+    (Input::KeyEnd, Key::End),
+    (Input::KeyPPage, Key::PageUp),
+    (Input::KeyNPage, Key::PageDown),
+    (Input::KeyIC, Key::Insert),
+    (Input::KeyDC, Key::Delete),
+    (Input::KeyF1, Key::F(1)),
+    (Input::KeyF2, Key::F(2)),
+    (Input::KeyF3, Key::F(3)),
+    (Input::KeyF4, Key::F(4)),
+    (Input::KeyF5, Key::F(5)),
+    (Input::KeyF6, Key::F(6)),
+    (Input::KeyF7, Key::F(7)),
+    (Input::KeyF8, Key::F(8)),
+    (Input::KeyF9, Key::F(9)),
+    (Input::KeyF10, Key::F(10)),
+    (Input::KeyF11, Key::F(11)),
+    (Input::KeyF12, Key::F(12)),
+];
+
+impl Input {
+    /// Returns the input with the Alt modifier added, or None if it can't be combined with Alt.
+    pub(crate) fn with_alt(&self) -> Option<Input> {
+        let (key, modifiers) = match *self {
+            Input::Character('\x1b') => return None,
+            Input::Character(c) => (Key::Character(c), Modifiers::NONE),
+            Input::KeyModified(key, modifiers) => (key, modifiers),
+            _ => {
+                let &(_, key) = PLAIN_KEYS.iter().find(|(input, _)| input == self)?;
+                (key, Modifiers::NONE)
+            }
+        };
+        Some(Input::KeyModified(key, modifiers | Modifiers::ALT))
+    }
+
+    /// The reverse of `with_alt()`, returns the input without the Alt modifier if it has it.
+    pub(crate) fn without_alt(&self) -> Option<Input> {
+        match *self {
+            Input::KeyModified(key, modifiers) if modifiers.contains(Modifiers::ALT) => {
+                let modifiers = Modifiers(modifiers.0 & !Modifiers::ALT.0);
+                if !modifiers.is_empty() {
+                    return Some(Input::KeyModified(key, modifiers));
+                }
+                match key {
+                    Key::Character(c) => Some(Input::Character(c)),
+                    _ => PLAIN_KEYS
+                        .iter()
+                        .find(|&&(_, k)| k == key)
+                        .map(|(input, _)| input.clone()),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A key that can be combined with modifiers, see `Input::KeyModified`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    /// A function key, from F1 to F24 for most terminals.
+    F(u8),
+    /// A character key, only reported with modifiers for Alt and Meta.
+    Character(char),
+}
+
+/// A set of modifier keys that were held down while pressing a key.
+///
+/// ```rust
+/// use pancurses::Modifiers;
+///
+/// let modifiers = Modifiers::CTRL | Modifiers::SHIFT;
+/// assert!(modifiers.contains(Modifiers::CTRL));
+/// assert!(!modifiers.contains(Modifiers::ALT));
+/// assert!(Modifiers::NONE.is_empty());
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers(0);
+    pub const SHIFT: Modifiers = Modifiers(1);
+    pub const ALT: Modifiers = Modifiers(2);
+    pub const CTRL: Modifiers = Modifiers(4);
+    pub const META: Modifiers = Modifiers(8);
+
+    /// Returns true if all the modifiers in other are in this set.
+    pub fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if no modifiers are in this set.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Decodes the modifier parameter of an xterm escape sequence, which is one more than the
+    /// bits of the set. The same numbering is used for the suffixes of terminfo key names.
+    pub(crate) fn from_parameter(parameter: u32) -> Modifiers {
+        Modifiers((parameter.saturating_sub(1) & 0xf) as u8)
+    }
+
+    /// The xterm modifier parameter for this set, see `from_parameter()`.
+    #[cfg_attr(windows, allow(dead_code))]
+    pub(crate) fn parameter(self) -> u32 {
+        u32::from(self.0) + 1
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, other: Modifiers) -> Modifiers {
+        Modifiers(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, other: Modifiers) {
+        self.0 |= other.0;
+    }
 }
@@ -0,0 +1,72 @@
+use std::os::unix::io::RawFd;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::io::unix::AsyncFd;
+use tokio::signal::unix::{signal, Signal, SignalKind};
+
+use crate::{input_fd, Error, Input, Result, Window};
+
+/// The input of a window as a `Stream`, returned by `Window::input_stream()`.
+///
+/// The stream waits for the terminal to become readable instead of blocking the thread, so it can
+/// be used with `select!` along with timers and network events. It reads the input with getch(),
+/// so it decodes everything getch() does, and also wakes up for `Input::KeyResize` when the
+/// terminal is resized. The stream ends if waiting for the terminal fails.
+///
+/// The window is in no-delay mode while the stream is alive, and gets its previous timeout back
+/// when the stream is dropped.
+#[derive(Debug)]
+pub struct InputStream<'a> {
+    window: &'a Window,
+    readable: AsyncFd<RawFd>,
+    resized: Signal,
+    timeout: i32,
+}
+
+impl<'a> InputStream<'a> {
+    pub(crate) fn new(window: &'a Window, timeout: i32) -> Result<InputStream<'a>> {
+        let to_error = |e: ::std::io::Error| Error::Io {
+            function: "input_stream",
+            kind: e.kind(),
+        };
+        let readable = AsyncFd::new(input_fd()).map_err(to_error)?;
+        let resized = signal(SignalKind::window_change()).map_err(to_error)?;
+        window.nodelay(true)?;
+        Ok(InputStream {
+            window,
+            readable,
+            resized,
+            timeout,
+        })
+    }
+}
+
+impl<'a> Stream for InputStream<'a> {
+    type Item = Input;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Input>> {
+        let stream = self.get_mut();
+        loop {
+            // Curses may already have read the input from the terminal into its own buffer
+            if let Some(input) = stream.window.getch() {
+                return Poll::Ready(Some(input));
+            }
+            if let Poll::Ready(Some(())) = stream.resized.poll_recv(cx) {
+                continue;
+            }
+            match stream.readable.poll_read_ready(cx) {
+                Poll::Ready(Ok(mut guard)) => guard.clear_ready(),
+                Poll::Ready(Err(_)) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<'a> Drop for InputStream<'a> {
+    fn drop(&mut self) {
+        self.window.timeout(self.timeout);
+    }
+}
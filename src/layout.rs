@@ -0,0 +1,443 @@
+use std::rc::{Rc, Weak};
+
+use crate::resize::place;
+use crate::{newwin, Error, ResizeManager, Result, SubWindow, Window};
+
+/// How much space one part of a `Layout` takes, in lines for rows and columns for columns.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Constraint {
+    /// Exactly this many lines or columns.
+    Fixed(i32),
+    /// This percentage of the space being split, rounded down.
+    Percentage(u16),
+    /// At least this many lines or columns, and a share of the space left over like `Fill(1)`.
+    Min(i32),
+    /// A share of the space left over like `Fill(1)`, but at most this many lines or columns.
+    Max(i32),
+    /// A share of the space left over after the other parts, in proportion to the weight.
+    Fill(u16),
+}
+
+/// A rectangle on the screen, with the size and position in the order newwin() takes them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub nlines: i32,
+    pub ncols: i32,
+    pub begy: i32,
+    pub begx: i32,
+}
+
+impl Rect {
+    /// Creates a rectangle of nlines by ncols with its top left corner at (begy, begx).
+    pub fn new(nlines: i32, ncols: i32, begy: i32, begx: i32) -> Rect {
+        Rect {
+            nlines,
+            ncols,
+            begy,
+            begx,
+        }
+    }
+
+    /// Returns the rectangle with the given number of lines and columns taken off each side.
+    pub fn inner(self, margin: i32) -> Rect {
+        Rect {
+            nlines: (self.nlines - 2 * margin).max(0),
+            ncols: (self.ncols - 2 * margin).max(0),
+            begy: self.begy + margin,
+            begx: self.begx + margin,
+        }
+    }
+}
+
+impl From<Rect> for (i32, i32, i32, i32) {
+    fn from(rect: Rect) -> (i32, i32, i32, i32) {
+        (rect.nlines, rect.ncols, rect.begy, rect.begx)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Direction {
+    Rows,
+    Columns,
+}
+
+/// Splits an area of the screen into rows or columns according to a list of constraints.
+///
+/// The space is handed out in two rounds. First `Fixed`, `Percentage` and `Min` parts get their
+/// sizes. What is left is then shared between the `Fill`, `Min` and `Max` parts in proportion to
+/// their weights, with `Max` parts stopping at their maximum. If the parts don't fit, the last ones
+/// are made smaller, down to nothing. split() returns the resulting rectangles, which can be split
+/// again with another layout to build up a grid.
+///
+/// windows() creates a window for each part, update() fits existing windows to a new area, and
+/// register() has a `ResizeManager` do that each time the terminal is resized. With borders, a box
+/// is drawn along the edges of each window, so the contents go inside it, starting at (1, 1).
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, Constraint, Layout, Rect, ResizeManager};
+/// use std::rc::Rc;
+///
+/// let window = initscr();
+/// let (lines, cols) = window.get_max_yx();
+/// // A title line, a body taking the rest of the screen and a status line
+/// let layout = Layout::rows(vec![
+///     Constraint::Fixed(1),
+///     Constraint::Fill(1),
+///     Constraint::Fixed(1),
+/// ]);
+/// let parts = layout.split(Rect::new(lines, cols, 0, 0));
+/// assert_eq!(Rect::new(lines - 2, cols, 1, 0), parts[1]);
+///
+/// // The body split into a sidebar a quarter of its width and a bordered main area
+/// let body = Layout::columns(vec![Constraint::Percentage(25), Constraint::Fill(1)]).borders(true);
+/// let windows: Vec<_> = body.windows(parts[1]).unwrap().into_iter().map(Rc::new).collect();
+/// assert_eq!((1, cols / 4), windows[1].get_beg_yx());
+///
+/// // Keep the body laid out like this when the terminal is resized
+/// let mut manager = ResizeManager::new();
+/// body.register(&mut manager, &windows, |lines, cols| Rect::new(lines - 2, cols, 1, 0));
+/// endwin();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Layout {
+    direction: Direction,
+    constraints: Vec<Constraint>,
+    margin: i32,
+    borders: bool,
+}
+
+impl Layout {
+    /// Creates a layout that splits an area into rows, from top to bottom.
+    pub fn rows(constraints: Vec<Constraint>) -> Layout {
+        Layout::new(Direction::Rows, constraints)
+    }
+
+    /// Creates a layout that splits an area into columns, from left to right.
+    pub fn columns(constraints: Vec<Constraint>) -> Layout {
+        Layout::new(Direction::Columns, constraints)
+    }
+
+    fn new(direction: Direction, constraints: Vec<Constraint>) -> Layout {
+        Layout {
+            direction,
+            constraints,
+            margin: 0,
+            borders: false,
+        }
+    }
+
+    /// Leaves the given number of lines and columns free around the edges of the area.
+    pub fn margin(mut self, margin: i32) -> Layout {
+        self.margin = margin;
+        self
+    }
+
+    /// Draws a box along the edges of each window of the layout.
+    pub fn borders(mut self, borders: bool) -> Layout {
+        self.borders = borders;
+        self
+    }
+
+    /// Splits the area into one rectangle per constraint.
+    ///
+    /// ```rust
+    /// use pancurses::{Constraint, Layout, Rect};
+    ///
+    /// let layout = Layout::columns(vec![
+    ///     Constraint::Min(10),
+    ///     Constraint::Max(5),
+    ///     Constraint::Fill(2),
+    /// ])
+    /// .margin(1);
+    /// let widths: Vec<i32> = layout.split(Rect::new(10, 42, 0, 0)).iter().map(|r| r.ncols).collect();
+    /// assert_eq!(vec![19, 5, 16], widths);
+    /// let widths: Vec<i32> = layout.split(Rect::new(10, 8, 0, 0)).iter().map(|r| r.ncols).collect();
+    /// assert_eq!(vec![6, 0, 0], widths);
+    /// ```
+    pub fn split(&self, area: Rect) -> Vec<Rect> {
+        let area = area.inner(self.margin);
+        let length = match self.direction {
+            Direction::Rows => area.nlines,
+            Direction::Columns => area.ncols,
+        };
+        let mut offset = 0;
+        self.sizes(length)
+            .into_iter()
+            .map(|size| {
+                let rect = match self.direction {
+                    Direction::Rows => Rect::new(size, area.ncols, area.begy + offset, area.begx),
+                    Direction::Columns => {
+                        Rect::new(area.nlines, size, area.begy, area.begx + offset)
+                    }
+                };
+                offset += size;
+                rect
+            })
+            .collect()
+    }
+
+    /// Creates a window for each part of the area.
+    ///
+    /// Curses takes a size of 0 to mean up to the edge of the screen, so a part that gets no space
+    /// is an `Error::EmptyWindow` instead.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, Constraint, Error, Layout, Rect};
+    ///
+    /// initscr();
+    /// let layout = Layout::rows(vec![Constraint::Fill(1), Constraint::Fixed(1)]);
+    /// assert_eq!(2, layout.windows(Rect::new(2, 10, 0, 0)).unwrap().len());
+    /// // The status line takes the only line there is
+    /// let error = layout.windows(Rect::new(1, 10, 0, 0)).unwrap_err();
+    /// assert_eq!(
+    ///     Error::EmptyWindow {
+    ///         function: "newwin",
+    ///         nlines: 0,
+    ///         ncols: 10
+    ///     },
+    ///     error
+    /// );
+    /// endwin();
+    /// ```
+    pub fn windows(&self, area: Rect) -> Result<Vec<Window>> {
+        self.split(area)
+            .into_iter()
+            .map(|rect| {
+                check_size("newwin", rect)?;
+                let window = newwin(rect.nlines, rect.ncols, rect.begy, rect.begx)?;
+                self.draw_border(&window)?;
+                Ok(window)
+            })
+            .collect()
+    }
+
+    /// Creates a subwindow of the parent for each part of the parent, which share its characters.
+    ///
+    /// Subwindows aren't laid out again when the terminal is resized, create them again after
+    /// resizing the parent instead. Like windows(), a part that gets no space is an error.
+    pub fn subwindows<'a>(&self, parent: &'a Window) -> Result<Vec<SubWindow<'a>>> {
+        let (lines, cols) = parent.get_max_yx();
+        self.split(Rect::new(lines, cols, 0, 0))
+            .into_iter()
+            .map(|rect| {
+                check_size("derwin", rect)?;
+                let window = parent.derwin(rect.nlines, rect.ncols, rect.begy, rect.begx)?;
+                self.draw_border(&window)?;
+                Ok(window)
+            })
+            .collect()
+    }
+
+    /// Resizes and moves windows created with windows() to fit the parts of a new area, and marks
+    /// them for the next doupdate().
+    ///
+    /// Windows whose parts get no space are left as they were. All the other windows are laid out
+    /// even so, and the first error is returned.
+    pub fn update<W: AsRef<Window>>(&self, windows: &[W], area: Rect) -> Result<()> {
+        let mut result = Ok(());
+        for (window, rect) in windows.iter().zip(self.split(area)) {
+            let placed = self.place(window.as_ref(), rect);
+            if result.is_ok() {
+                result = placed;
+            }
+        }
+        result
+    }
+
+    /// Has the manager lay out the windows each time the terminal is resized, in the area the
+    /// callback returns for the number of lines and columns of the screen. Windows whose parts get
+    /// no space are left as they were, like with update().
+    pub fn register<'a, W, F>(
+        &self,
+        manager: &mut ResizeManager<'a>,
+        windows: &[Rc<W>],
+        mut area: F,
+    ) where
+        W: AsRef<Window> + 'a,
+        F: FnMut(i32, i32) -> Rect + 'a,
+    {
+        let layout = self.clone();
+        let windows: Vec<Weak<W>> = windows.iter().map(Rc::downgrade).collect();
+        manager.add(Box::new(move |lines, cols| {
+            let windows: Vec<_> = windows.iter().map(Weak::upgrade).collect();
+            if windows.iter().all(Option::is_none) {
+                return None;
+            }
+            let mut result = Ok(());
+            for (window, rect) in windows.iter().zip(layout.split(area(lines, cols))) {
+                if let Some(ref window) = *window {
+                    let placed = layout.place((**window).as_ref(), rect);
+                    if result.is_ok() {
+                        result = placed;
+                    }
+                }
+            }
+            Some(result)
+        }));
+    }
+
+    fn place(&self, window: &Window, rect: Rect) -> Result<()> {
+        check_size("wresize", rect)?;
+        place(window, rect.into())?;
+        self.draw_border(window)
+    }
+
+    fn draw_border(&self, window: &Window) -> Result<()> {
+        if self.borders {
+            window.draw_box(0, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Works out the size of each part for the given length.
+    fn sizes(&self, length: i32) -> Vec<i32> {
+        let length = length.max(0);
+        let mut sizes: Vec<i32> = self
+            .constraints
+            .iter()
+            .map(|constraint| match *constraint {
+                Constraint::Fixed(size) | Constraint::Min(size) => size.max(0),
+                Constraint::Percentage(percent) => {
+                    (i64::from(length) * i64::from(percent) / 100) as i32
+                }
+                Constraint::Max(_) | Constraint::Fill(_) => 0,
+            })
+            .collect();
+        let mut left = length - sizes.iter().sum::<i32>();
+
+        if left < 0 {
+            // Take the space that is missing from the last parts
+            for size in sizes.iter_mut().rev() {
+                let taken = (*size).min(-left);
+                *size -= taken;
+                left += taken;
+            }
+            return sizes;
+        }
+
+        // The parts that share what is left, with their weights and the most they can take
+        let mut sharing: Vec<(usize, i64, Option<i32>)> = self
+            .constraints
+            .iter()
+            .enumerate()
+            .filter_map(|(i, constraint)| match *constraint {
+                Constraint::Min(_) => Some((i, 1, None)),
+                Constraint::Max(max) => Some((i, 1, Some(max.max(0)))),
+                Constraint::Fill(weight) if weight > 0 => Some((i, i64::from(weight), None)),
+                _ => None,
+            })
+            .collect();
+        loop {
+            let total: i64 = sharing.iter().map(|&(_, weight, _)| weight).sum();
+            if total == 0 || left == 0 {
+                return sizes;
+            }
+            let share = |weight: i64| (i64::from(left) * weight / total) as i32;
+            // Parts that would get more than their maximum get their maximum, the others then
+            // share what is left after that
+            let capped = sharing.iter().position(|&(_, weight, max)| match max {
+                Some(max) => share(weight) >= max,
+                None => false,
+            });
+            if let Some(position) = capped {
+                let (i, _, max) = sharing.remove(position);
+                sizes[i] = max.unwrap_or(0);
+                left -= sizes[i];
+                continue;
+            }
+            let mut given = 0;
+            for &(i, weight, _) in &sharing {
+                let size = share(weight);
+                sizes[i] += size;
+                given += size;
+            }
+            // Hand out what rounding down left over one at a time, from the first part
+            for &(i, _, _) in sharing.iter().take((left - given) as usize) {
+                sizes[i] += 1;
+            }
+            return sizes;
+        }
+    }
+}
+
+/// Returns an error for a part that got no space, which curses would stretch to the edge of the
+/// screen.
+fn check_size(function: &'static str, rect: Rect) -> Result<()> {
+    if rect.nlines <= 0 || rect.ncols <= 0 {
+        return Err(Error::EmptyWindow {
+            function,
+            nlines: rect.nlines,
+            ncols: rect.ncols,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sizes(constraints: Vec<Constraint>, length: i32) -> Vec<i32> {
+        Layout::rows(constraints).sizes(length)
+    }
+
+    #[test]
+    fn test_fixed_and_percentage() {
+        use self::Constraint::*;
+        assert_eq!(vec![3, 5], sizes(vec![Fixed(3), Fixed(5)], 20));
+        assert_eq!(vec![5, 10], sizes(vec![Percentage(25), Percentage(50)], 20));
+        // Percentages are rounded down
+        assert_eq!(vec![3], sizes(vec![Percentage(33)], 10));
+    }
+
+    #[test]
+    fn test_fill_weights() {
+        use self::Constraint::*;
+        assert_eq!(vec![2, 6, 12], sizes(vec![Fixed(2), Fill(1), Fill(2)], 20));
+        // What rounding down leaves over goes to the first parts
+        assert_eq!(vec![4, 3, 3], sizes(vec![Fill(1), Fill(1), Fill(1)], 10));
+        // Parts with no weight get nothing
+        assert_eq!(vec![0, 10], sizes(vec![Fill(0), Fill(1)], 10));
+    }
+
+    #[test]
+    fn test_min_and_max() {
+        use self::Constraint::*;
+        // Min gets its minimum and a share of the rest
+        assert_eq!(vec![7, 3], sizes(vec![Min(4), Fill(1)], 10));
+        // Max stops at its maximum and leaves the rest to the others
+        assert_eq!(vec![2, 8], sizes(vec![Max(2), Fill(1)], 10));
+        assert_eq!(vec![5, 5], sizes(vec![Max(8), Fill(1)], 10));
+        assert_eq!(vec![3, 3, 14], sizes(vec![Max(3), Max(3), Fill(2)], 20));
+    }
+
+    #[test]
+    fn test_not_enough_space() {
+        use self::Constraint::*;
+        // The last parts are made smaller, down to nothing
+        assert_eq!(vec![6, 2, 0], sizes(vec![Fixed(6), Fixed(4), Min(3)], 8));
+        assert_eq!(vec![0, 0], sizes(vec![Fixed(2), Fill(1)], 0));
+        assert_eq!(vec![0], sizes(vec![Fixed(2)], -5));
+    }
+
+    #[test]
+    fn test_split() {
+        let layout = Layout::columns(vec![Constraint::Fixed(4), Constraint::Fill(1)]).margin(1);
+        assert_eq!(
+            vec![Rect::new(8, 4, 1, 3), Rect::new(8, 14, 1, 7)],
+            layout.split(Rect::new(10, 20, 0, 2))
+        );
+        let layout = Layout::rows(vec![Constraint::Fixed(4), Constraint::Fill(1)]);
+        assert_eq!(
+            vec![Rect::new(4, 20, 5, 0), Rect::new(6, 20, 9, 0)],
+            layout.split(Rect::new(10, 20, 5, 0))
+        );
+    }
+
+    #[test]
+    fn test_check_size() {
+        assert!(check_size("newwin", Rect::new(1, 1, 0, 0)).is_ok());
+        assert!(check_size("newwin", Rect::new(0, 1, 0, 0)).is_err());
+        assert!(check_size("newwin", Rect::new(1, 0, 0, 0)).is_err());
+    }
+}
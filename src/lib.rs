@@ -4,6 +4,13 @@
 extern crate log;
 
 extern crate libc;
+extern crate unicode_width;
+#[cfg(feature = "regex")]
+extern crate regex;
+#[cfg(feature = "async")]
+extern crate futures_core;
+#[cfg(all(unix, feature = "async"))]
+extern crate tokio;
 
 #[cfg(unix)]
 extern crate ncurses;
@@ -20,6 +27,7 @@ pub use pdcurses::{chtype, mmask_t, MEVENT, SCREEN};
 #[cfg(windows)]
 type ScrPtr = *mut SCREEN;
 #[cfg(windows)]
+#[allow(clippy::upper_case_acronyms)]
 type FILE = *mut curses::FILE;
 
 #[cfg(unix)]
@@ -29,8 +37,16 @@ pub use ncurses::ll::{chtype, mmask_t, MEVENT, SCREEN};
 #[cfg(unix)]
 type ScrPtr = SCREEN;
 #[cfg(unix)]
+#[allow(clippy::upper_case_acronyms)]
 type FILE = curses::FILE_p;
 
+#[macro_use]
+mod macros;
+
+mod error;
+pub use self::error::{Error, Result, ReturnCode};
+use self::error::{check, check_ptr};
+
 mod input;
 pub use self::input::*;
 
@@ -40,6 +56,51 @@ pub use self::attributes::*;
 pub mod colorpair;
 pub use crate::colorpair::ColorPair;
 
+mod complexchar;
+pub use crate::complexchar::{ComplexChar, CCHARW_MAX};
+
+mod width;
+pub use crate::width::display_width;
+
+mod pad;
+pub use crate::pad::{Pad, SubPad};
+
+mod panel;
+pub use crate::panel::{new_panel, update_panels, Panel};
+
+mod list_state;
+
+mod menu;
+pub use crate::menu::{Menu, MenuEvent, MenuItem};
+
+mod list_view;
+pub use crate::list_view::{ListEvent, ListItem, ListView};
+
+mod form;
+pub use crate::form::{Field, Form, FormEvent, Validator};
+
+mod line_editor;
+pub use crate::line_editor::{Completer, LineEditor};
+
+mod escape;
+pub use crate::escape::set_alt_decoding;
+
+mod modes;
+pub use crate::modes::{set_bracketed_paste, set_focus_reporting, set_mouse_tracking, MouseTracking};
+
+mod mouse;
+pub use crate::mouse::{MouseButton, MouseEvent, MouseEventKind};
+
+#[cfg(unix)]
+mod waker;
+#[cfg(unix)]
+pub use crate::waker::Waker;
+
+#[cfg(all(unix, feature = "async"))]
+mod input_stream;
+#[cfg(all(unix, feature = "async"))]
+pub use crate::input_stream::InputStream;
+
 #[cfg(windows)]
 mod windows;
 #[cfg(windows)]
@@ -58,12 +119,26 @@ pub const OK: i32 = 0;
 pub const ERR: i32 = -1;
 
 mod window;
-pub use crate::window::Window;
+pub use crate::window::{ControlChars, SubWindow, Window};
+
+mod session;
+pub use crate::session::Session;
+
+mod resize;
+pub use crate::resize::ResizeManager;
+
+mod layout;
+pub use crate::layout::{Constraint, Layout, Rect};
+
+mod event_loop;
+pub use crate::event_loop::{EventContext, EventLoop, TimerId};
 
 pub trait ToChtype {
     fn to_chtype(&self) -> chtype;
 }
 
+/// On ncurses a chtype only has room for an 8-bit character, so anything outside of Latin-1 is
+/// mangled. Use `ComplexChar` and the wide-character functions such as `Window::add_wch()` instead.
 impl ToChtype for char {
     fn to_chtype(&self) -> chtype {
         *self as chtype
@@ -82,8 +157,8 @@ pub fn baudrate() -> i32 {
 }
 
 /// Sounds the audible bell on the terminal, if possible; if not, it calls flash().
-pub fn beep() -> i32 {
-    unsafe { curses::beep() }
+pub fn beep() -> Result<()> {
+    check("beep", unsafe { curses::beep() })
 }
 
 /// Indicates if the terminal has the capability to change the definition of its colors.
@@ -97,8 +172,8 @@ pub fn can_change_color() -> bool {
 /// character processing is not performed.  In nocbreak mode, typed characters are buffered until
 /// a newline or carriage return. Interrupt and flow control characters are unaffected by this
 /// mode.
-pub fn cbreak() -> i32 {
-    unsafe { curses::cbreak() }
+pub fn cbreak() -> Result<()> {
+    check("cbreak", unsafe { curses::cbreak() })
 }
 
 /// Maximum number of colors the terminal is capable of displaying.
@@ -145,27 +220,32 @@ pub fn color_content(color_number: i16) -> (i16, i16, i16) {
 ///
 /// A visibility of 0 makes it disappear; 1 makes it appear "normal" (usually an underline) and 2
 /// makes it "highly visible" (usually a block).
-pub fn curs_set(visibility: i32) -> i32 {
-    unsafe { curses::curs_set(visibility) }
+///
+/// Returns the previous visibility.
+pub fn curs_set(visibility: i32) -> Result<i32> {
+    let previous = unsafe { curses::curs_set(visibility) };
+    check("curs_set", previous).map(|_| previous)
 }
 
 /// Save the current terminal modes as the "program" (in curses) state for use by the
 /// `reset_prog_mode()` and `reset_shell_mode()` functions.  This is done automatically by initscr().
-pub fn def_prog_mode() -> i32 {
-    unsafe { curses::def_prog_mode() }
+pub fn def_prog_mode() -> Result<()> {
+    check("def_prog_mode", unsafe { curses::def_prog_mode() })
 }
 
 /// Save the current terminal modes as the "shell" (not in curses) state for use by the
 /// `reset_prog_mode()` and `reset_shell_mode()` functions.  This is done automatically by initscr().
-pub fn def_shell_mode() -> i32 {
-    unsafe { curses::def_shell_mode() }
+pub fn def_shell_mode() -> Result<()> {
+    check("def_shell_mode", unsafe { curses::def_shell_mode() })
 }
 
 /// Inserts an 'milliseconds' millisecond pause in output. This routine should not be used extensively
 /// because padding characters are used rather than a CPU pause. If no padding character is
 /// specified, this uses napms to perform the delay.
-pub fn delay_output(milliseconds: i32) -> i32 {
-    unsafe { curses::delay_output(milliseconds) }
+pub fn delay_output(milliseconds: i32) -> Result<()> {
+    check("delay_output", unsafe {
+        curses::delay_output(milliseconds)
+    })
 }
 
 /// Frees storage associated with the SCREEN data structure.
@@ -174,22 +254,23 @@ pub fn delay_output(milliseconds: i32) -> i32 {
 /// SCREEN is no longer needed.
 ///
 /// In PDCurses, the parameter must be the value of SP, and delscreen() sets SP to NULL.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn delscreen(screen: ScrPtr) {
     unsafe { curses::delscreen(screen) }
 }
 
 /// Compares the virtual screen to the physical screen and performs an update of the physical
 /// screen.
-pub fn doupdate() -> i32 {
-    unsafe { curses::doupdate() }
+pub fn doupdate() -> Result<()> {
+    check("doupdate", unsafe { curses::doupdate() })
 }
 
 /// Enabled echoing typed characters.
 ///
 /// Initially, input characters are echoed. Subsequent calls to echo() and noecho() do not flush
 /// type-ahead.
-pub fn echo() -> i32 {
-    unsafe { curses::echo() }
+pub fn echo() -> Result<()> {
+    check("echo", unsafe { curses::echo() })
 }
 
 /// Should be called before exiting or escaping from curses mode temporarily.
@@ -197,32 +278,45 @@ pub fn echo() -> i32 {
 /// It will restore tty modes, move the cursor to the lower left corner of the screen and reset the
 /// terminal into the proper non-visual mode.  To resume curses after a temporary escape, call
 /// refresh() or doupdate().
-pub fn endwin() -> i32 {
-    unsafe { curses::endwin() }
+pub fn endwin() -> Result<()> {
+    modes::reset();
+    session::set_initscr(false);
+    check("endwin", unsafe { curses::endwin() })
 }
 
 /// Flashes the screen, if possible; if not, it calls beep().
-pub fn flash() -> i32 {
-    unsafe { curses::flash() }
+pub fn flash() -> Result<()> {
+    check("flash", unsafe { curses::flash() })
 }
 
 /// Throws away any type-ahead that has been typed by the user and has not yet been read by the
 /// program.
-pub fn flushinp() -> i32 {
-    unsafe { curses::flushinp() }
+pub fn flushinp() -> Result<()> {
+    check("flushinp", unsafe { curses::flushinp() })
+}
+
+/// Returns the escape delay in milliseconds, see `set_escdelay()`.
+pub fn get_escdelay() -> i32 {
+    platform_specific::_get_escdelay()
 }
 
 /// Returns the current mouse status in an MEVENT struct.
-pub fn getmouse() -> Result<MEVENT, i32> {
-    platform_specific::_getmouse()
+///
+/// getch() already reads the mouse event when curses reports one and returns it as
+/// `Input::Mouse`, so this is only needed to read the raw event after `Input::KeyMouse` from
+/// another source.
+pub fn getmouse() -> Result<MEVENT> {
+    platform_specific::_getmouse().map_err(|_| Error::Curses {
+        function: "getmouse",
+    })
 }
 
 /// Similar to cbreak(), but allows for a time limit to be specified, in tenths of a second.
 ///
 /// This causes getch() to block for that period before returning None if no key has been received.
 /// tenths must be between 1 and 255.
-pub fn half_delay(tenths: i32) -> i32 {
-    unsafe { curses::halfdelay(tenths) }
+pub fn half_delay(tenths: i32) -> Result<()> {
+    check("halfdelay", unsafe { curses::halfdelay(tenths) })
 }
 
 /// Indicates if the terminal supports, and can maniplulate color.
@@ -232,8 +326,20 @@ pub fn has_colors() -> bool {
 
 /// Initialize the curses system, this must be the first function that is called.
 ///
-/// Returns a Window struct that is used to access Window specific functions.
+/// Returns a Window struct that is used to access Window specific functions. It is up to the
+/// caller to call endwin() before exiting, see `Session` for a guard that does it automatically.
+///
+/// # Panics
+///
+/// Panics if a `Session` is active, as the returned Window would alias its standard screen. This
+/// function can't report errors, use `Session::new()` or `try_initscr()` where a session may be
+/// active, which return `Error::AlreadyInitialized` instead. For the same reason sessions can't
+/// be started after initscr() has been called, until endwin() is called.
 pub fn initscr() -> Window {
+    if session::is_active() {
+        panic!("initscr() called while a pancurses Session is alive");
+    }
+    session::set_initscr(true);
     platform_specific::pre_init();
     let window_pointer = unsafe { curses::initscr() };
     window::new_window(window_pointer, true)
@@ -244,8 +350,10 @@ pub fn initscr() -> Window {
 /// The first argument must be a legal color value; default colors are not allowed here.
 /// Each of the last three arguments must be a value in the range 0 through 1000. When `init_color`
 /// is used, all occurrences of that color on the screen immediately change to the new definition.
-pub fn init_color(color_number: i16, red: i16, green: i16, blue: i16) -> i32 {
-    unsafe { curses::init_color(color_number, red, green, blue) }
+pub fn init_color(color_number: i16, red: i16, green: i16, blue: i16) -> Result<()> {
+    check("init_color", unsafe {
+        curses::init_color(color_number, red, green, blue)
+    })
 }
 
 /// Changes the definition of a color-pair.
@@ -255,8 +363,23 @@ pub fn init_color(color_number: i16, red: i16, green: i16, blue: i16) -> i32 {
 /// inclusive. The foreground and background must be between 0 and `COLORS()` - 1, inclusive. If the
 /// color pair was previously initialized, the screen is refreshed, and all occurrences of that
 /// color-pair are changed to the new definition.
-pub fn init_pair(pair_index: i16, foreground_color: i16, background_color: i16) -> i32 {
-    unsafe { curses::init_pair(pair_index, foreground_color, background_color) as i32 }
+pub fn init_pair(pair_index: i16, foreground_color: i16, background_color: i16) -> Result<()> {
+    check("init_pair", unsafe {
+        curses::init_pair(pair_index, foreground_color, background_color) as i32
+    })
+}
+
+/// Returns the file descriptor that curses reads the input for the standard screen from, for
+/// waiting for input with poll() or an event loop. This is the standard input, the duplicate of it
+/// that try_initscr() reads from, or the input of the terminal last opened with newterm().
+///
+/// Curses buffers some of the input itself, so keep calling getch() in nodelay mode until it
+/// returns None before waiting for the descriptor to become readable again.
+///
+/// (Only supported on Unix)
+#[cfg(unix)]
+pub fn input_fd() -> std::os::unix::io::RawFd {
+    platform_specific::_input_fd()
 }
 
 /// Sets the timeout for a mouse click.
@@ -302,8 +425,8 @@ pub fn keyname(code: i32) -> Option<String> {
 }
 
 /// Suspends the program for the specified number of milliseconds.
-pub fn napms(ms: i32) -> i32 {
-    unsafe { curses::napms(ms) }
+pub fn napms(ms: i32) -> Result<()> {
+    check("napms", unsafe { curses::napms(ms) })
 }
 
 /// A program that outputs to more than one terminal should use the newterm routine for each
@@ -316,13 +439,29 @@ pub fn napms(ms: i32) -> i32 {
 ///
 /// (For the PDCurses backend it's just an alternative interface for initscr(). It always returns
 /// SP, or NULL.)
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn newterm(t: Option<&str>, output: FILE, input: FILE) -> ScrPtr {
     let term_type = t.map(|x| CString::new(x).unwrap());
     let type_ptr = match term_type {
         Some(ref s) => s.as_ptr(),
         _ => std::ptr::null(),
     };
-    unsafe { curses::newterm(type_ptr, output, input) }
+    let screen = unsafe { curses::newterm(type_ptr, output, input) };
+    #[cfg(unix)]
+    {
+        if !screen.is_null() {
+            platform_specific::_use_streams(output, input);
+        }
+    }
+    screen
+}
+
+/// Creates a new pad with the given number of lines, nlines and columns, ncols.
+///
+/// A pad can be larger than the screen, see `Pad` for how it's shown on the screen.
+pub fn newpad(nlines: i32, ncols: i32) -> Result<Pad> {
+    let window_pointer = check_ptr("newpad", unsafe { curses::newpad(nlines, ncols) })?;
+    Ok(pad::new_pad(window::new_window(window_pointer, false)))
 }
 
 /// Creates a new window with the given number of lines, nlines and columns, ncols.
@@ -330,16 +469,18 @@ pub fn newterm(t: Option<&str>, output: FILE, input: FILE) -> ScrPtr {
 /// The upper left corner of the window is at line begy, column begx. If nlines is zero, it
 /// defaults to LINES - begy; ncols to COLS - begx. Create a new full-screen window by calling
 /// newwin(0, 0, 0, 0).
-pub fn newwin(nlines: i32, ncols: i32, begy: i32, begx: i32) -> Window {
-    let window_pointer = unsafe { curses::newwin(nlines, ncols, begy, begx) };
-    window::new_window(window_pointer, false)
+pub fn newwin(nlines: i32, ncols: i32, begy: i32, begx: i32) -> Result<Window> {
+    let window_pointer = check_ptr("newwin", unsafe {
+        curses::newwin(nlines, ncols, begy, begx)
+    })?;
+    Ok(window::new_window(window_pointer, false))
 }
 
 /// Enables the translation of a carriage return into a newline on input.
 ///
 /// nonl() disables this. Initially, the translation does occur.
-pub fn nl() -> i32 {
-    unsafe { curses::nl() }
+pub fn nl() -> Result<()> {
+    check("nl", unsafe { curses::nl() })
 }
 
 /// Set nocbreak mode.
@@ -348,23 +489,23 @@ pub fn nl() -> i32 {
 /// character processing is not performed.  In nocbreak mode, typed characters are buffered until
 /// a newline or carriage return. Interrupt and flow control characters are unaffected by this
 /// mode.
-pub fn nocbreak() -> i32 {
-    unsafe { curses::nocbreak() }
+pub fn nocbreak() -> Result<()> {
+    check("nocbreak", unsafe { curses::nocbreak() })
 }
 
 /// Disables echoing typed characters.
 ///
 /// Initially, input characters are echoed. Subsequent calls to echo() and noecho() do not flush
 /// type-ahead.
-pub fn noecho() -> i32 {
-    unsafe { curses::noecho() }
+pub fn noecho() -> Result<()> {
+    check("noecho", unsafe { curses::noecho() })
 }
 
 /// Disables the translation of a carriage return into a newline on input.
 ///
 /// nl() enables this. Initially, the translation does occur.
-pub fn nonl() -> i32 {
-    unsafe { curses::nonl() }
+pub fn nonl() -> Result<()> {
+    check("nonl", unsafe { curses::nonl() })
 }
 
 /// Disable raw mode.
@@ -372,8 +513,8 @@ pub fn nonl() -> i32 {
 /// Raw mode is similar to cbreak mode, in that characters typed are immediately passed through to
 /// the user program. The difference is that in raw mode, the INTR, QUIT, SUSP, and STOP characters
 /// are passed through without being interpreted, and without generating a signal.
-pub fn noraw() -> i32 {
-    unsafe { curses::noraw() }
+pub fn noraw() -> Result<()> {
+    check("noraw", unsafe { curses::noraw() })
 }
 
 /// Enable raw mode.
@@ -381,21 +522,21 @@ pub fn noraw() -> i32 {
 /// Raw mode is similar to cbreak mode, in that characters typed are immediately passed through to
 /// the user program. The difference is that in raw mode, the INTR, QUIT, SUSP, and STOP characters
 /// are passed through without being interpreted, and without generating a signal.
-pub fn raw() -> i32 {
-    unsafe { curses::raw() }
+pub fn raw() -> Result<()> {
+    check("raw", unsafe { curses::raw() })
 }
 
 /// Restore the terminal to "program" (in curses) state. This is done
 /// automatically by endwin() and doupdate() after an endwin(), so this would normally not be
 /// called before.
-pub fn reset_prog_mode() -> i32 {
-    unsafe { curses::reset_prog_mode() }
+pub fn reset_prog_mode() -> Result<()> {
+    check("reset_prog_mode", unsafe { curses::reset_prog_mode() })
 }
 
 /// Restore the terminal to "shell" (not in curses) state. This is done automatically by
 /// endwin() and doupdate() after an endwin(), so this would normally not be called before.
-pub fn reset_shell_mode() -> i32 {
-    unsafe { curses::reset_shell_mode() }
+pub fn reset_shell_mode() -> Result<()> {
+    check("reset_shell_mode", unsafe { curses::reset_shell_mode() })
 }
 
 /// Attempts to resize the screen to the given size.
@@ -407,8 +548,11 @@ pub fn reset_shell_mode() -> i32 {
 /// and/or call `is_termresized()` at appropriate times; if either condition occurs, call
 /// `resize_term(0, 0)`. Then, with either user or programmatic resizing, you'll have to resize any
 /// windows you've created.
-pub fn resize_term(nlines: i32, ncols: i32) -> i32 {
-    platform_specific::_resize_term(nlines, ncols)
+pub fn resize_term(nlines: i32, ncols: i32) -> Result<()> {
+    check(
+        "resize_term",
+        platform_specific::_resize_term(nlines, ncols),
+    )
 }
 
 /// Toggles whether the `A_BLINK` attribute sets an actual blink mode (TRUE), or sets the background
@@ -418,8 +562,33 @@ pub fn resize_term(nlines: i32, ncols: i32) -> i32 {
 /// state to match the given parameter, ERR otherwise. Current platforms also adjust the value
 /// of COLORS() according to this function -- 16 for FALSE, and 8 for TRUE.
 /// (Only supported on Windows)
-pub fn set_blink(enabled: bool) -> i32 {
-    platform_specific::_set_blink(enabled)
+pub fn set_blink(enabled: bool) -> Result<()> {
+    check("PDC_set_blink", platform_specific::_set_blink(enabled))
+}
+
+/// Sets how long to wait after an escape character for the rest of an escape sequence or Alt key,
+/// in milliseconds.
+///
+/// With keypad() enabled ncurses waits this long before returning a lone escape character, and
+/// getch() waits this long for an Alt key when `set_alt_decoding()` is enabled. A shorter delay
+/// makes the Escape key more responsive, but a delay that is too short splits up escape sequences
+/// that arrive slowly, e.g. over a network connection. On ncurses the default comes from the
+/// ESCDELAY environment variable and is a second if it isn't set. PDCurses reports Alt keys with
+/// codes of their own, so there the default is no delay at all.
+///
+/// ```rust
+/// use pancurses::{endwin, get_escdelay, initscr, set_escdelay};
+///
+/// initscr();
+/// set_escdelay(25).unwrap();
+/// assert_eq!(25, get_escdelay());
+/// endwin();
+/// ```
+pub fn set_escdelay(milliseconds: i32) -> Result<()> {
+    check(
+        "set_escdelay",
+        platform_specific::_set_escdelay(milliseconds),
+    )
 }
 
 /// Switches between different terminals.
@@ -430,6 +599,7 @@ pub fn set_blink(enabled: bool) -> i32 {
 ///
 /// (Does nothing meaningful in PDCurses, but is included for compatibility with other curses
 /// implementations.)
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn set_term(new: ScrPtr) -> ScrPtr {
     unsafe { curses::set_term(new) }
 }
@@ -443,14 +613,44 @@ pub fn set_title(title: &str) {
 /// Initializes eight basic colors (black, red, green, yellow, blue, magenta, cyan,
 /// and white), and two global variables accessed through `COLORS()` and `COLOR_PAIRS()` (respectively defining the
 /// maximum number of colors and color-pairs the terminal is capable of displaying).
-pub fn start_color() -> i32 {
-    unsafe { curses::start_color() as i32 }
+pub fn start_color() -> Result<()> {
+    check("start_color", unsafe { curses::start_color() as i32 })
+}
+
+/// Initialize the curses system like initscr(), but report failure as an error instead of
+/// exiting the process.
+///
+/// On ncurses, initscr() prints an error and exits if the terminal can't be initialized. This
+/// function is built on newterm() and checks for those cases up front, returning
+/// `Error::NotATty` if the standard input or output isn't a terminal, `Error::UnknownTerminal` if
+/// `TERM` isn't set or isn't in the terminfo database and `Error::MissingTerminfo` if there is no
+/// terminfo database at all. This allows falling back to plain line output.
+///
+/// On success the terminal is owned by the returned `Session`. Windows created with newwin() can
+/// outlive the session, in which case the memory curses keeps for the terminal isn't freed when
+/// the session ends, as freeing it would free those windows as well.
+///
+/// ```rust
+/// use pancurses::try_initscr;
+///
+/// match try_initscr() {
+///     Ok(session) => {
+///         session.printw("Hello Rust").unwrap();
+///         session.refresh().unwrap();
+///     }
+///     Err(error) => println!("Hello Rust (curses is unavailable: {})", error),
+/// }
+/// ```
+pub fn try_initscr() -> Result<Session> {
+    Session::with_newterm()
 }
 
 /// Allows the use of -1 as a foreground or background color with `init_pair()`.
 ///
 /// Calls `assume_default_colors(-1, -1);` -1 represents the foreground or background color that
 /// the terminal had at startup.
-pub fn use_default_colors() -> i32 {
-    unsafe { curses::use_default_colors() }
+pub fn use_default_colors() -> Result<()> {
+    check("use_default_colors", unsafe {
+        curses::use_default_colors()
+    })
 }
@@ -0,0 +1,260 @@
+use std::fmt;
+
+use crate::width::char_width;
+use crate::{Input, Window};
+
+/// A function that completes a line, returning the completed line or None if there's nothing to
+/// complete.
+pub type Completer = Box<dyn FnMut(&str) -> Option<String>>;
+
+/// Reads lines of text from a window with a line editor.
+///
+/// The line is echoed at the cursor position of the window and scrolls horizontally if it doesn't
+/// fit on the rest of the line. The window should have keypad() enabled and echo should be turned
+/// off with noecho(), since the editor echoes the line itself.
+///
+/// The following keys are understood:
+///
+/// - The left and right arrow keys move the cursor, Home and End (or Ctrl-A and Ctrl-E) move it to
+///   the start or the end of the line.
+/// - Backspace deletes the character before the cursor, Delete the character under it and Ctrl-W
+///   the word before it. Ctrl-U deletes everything before the cursor.
+/// - Insert switches between inserting and overwriting characters.
+/// - The up and down arrow keys move through the history of lines that were read before.
+/// - Tab calls the completer, if one has been set.
+/// - Enter accepts the line and Esc cancels reading it.
+/// - Pasted text is inserted at the cursor, with line breaks turned into spaces, when bracketed
+///   paste is enabled with `set_bracketed_paste()`.
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, noecho, Input, LineEditor};
+///
+/// let window = initscr();
+/// noecho().unwrap();
+/// window.keypad(true).unwrap();
+///
+/// let mut editor = LineEditor::new();
+/// editor.set_completer(|line| if "hello".starts_with(line) { Some("hello".to_string()) } else { None });
+/// // ungetch() works like a stack, so the input is pushed in reverse
+/// for input in [Input::Character('h'), Input::Character('\t'), Input::Character('!'), Input::Character('\n')]
+///     .iter()
+///     .rev()
+/// {
+///     window.ungetch(input).unwrap();
+/// }
+/// assert_eq!(Some("hello!".to_string()), editor.read_line(&window, 80));
+/// assert_eq!(&["hello!".to_string()], editor.history());
+/// endwin();
+/// ```
+#[derive(Default)]
+pub struct LineEditor {
+    history: Vec<String>,
+    completer: Option<Completer>,
+}
+
+impl fmt::Debug for LineEditor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LineEditor")
+            .field("history", &self.history)
+            .field("completer", &self.completer.is_some())
+            .finish()
+    }
+}
+
+/// The characters of pasted text that can go on a single line, with line breaks turned into spaces.
+pub(crate) fn pasted_chars(text: &str) -> impl Iterator<Item = char> + '_ {
+    text.lines()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            let separator = if i > 0 { Some(' ') } else { None };
+            separator.into_iter().chain(line.chars())
+        })
+        .filter(|c| !c.is_control())
+}
+
+/// The state of the line while it's being edited.
+struct Line {
+    chars: Vec<char>,
+    cursor: usize,
+    insert: bool,
+}
+
+impl Line {
+    fn set(&mut self, text: &str, max: usize) {
+        self.chars = text.chars().take(max).collect();
+        self.cursor = self.chars.len();
+    }
+
+    fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    fn put(&mut self, c: char, max: usize) {
+        if !self.insert && self.cursor < self.chars.len() {
+            self.chars[self.cursor] = c;
+            self.cursor += 1;
+        } else if self.chars.len() < max {
+            self.chars.insert(self.cursor, c);
+            self.cursor += 1;
+        }
+    }
+
+    fn delete_word(&mut self) {
+        let mut start = self.cursor;
+        while start > 0 && self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        while start > 0 && !self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        self.chars.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    /// Shows the line at (y, x), scrolled so that the cursor fits into width columns.
+    fn draw(&self, window: &Window, y: i32, x: i32, width: usize) {
+        let width_of = |chars: &[char]| -> usize { chars.iter().cloned().map(char_width).sum() };
+        let mut start = 0;
+        while start < self.cursor && width_of(&self.chars[start..self.cursor]) >= width {
+            start += 1;
+        }
+        let visible: String = self.chars[start..].iter().collect();
+        // Drawing fails in the bottom right corner of the window, which doesn't matter here
+        let _ = window.mv(y, x);
+        let _ = window.clrtoeol();
+        let _ = window.addstr_cols(visible, width, None);
+        let _ = window.mv(y, x + width_of(&self.chars[start..self.cursor]) as i32);
+        let _ = window.refresh();
+    }
+}
+
+impl LineEditor {
+    /// Creates a line editor with an empty history and no completer.
+    pub fn new() -> LineEditor {
+        LineEditor::default()
+    }
+
+    /// The lines that were read, from the oldest to the newest.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Adds a line to the end of the history.
+    pub fn add_history<T: Into<String>>(&mut self, line: T) {
+        self.history.push(line.into());
+    }
+
+    /// Sets the function that is called with the line when Tab is pressed.
+    pub fn set_completer<F>(&mut self, completer: F)
+    where
+        F: FnMut(&str) -> Option<String> + 'static,
+    {
+        self.completer = Some(Box::new(completer));
+    }
+
+    /// Reads a line of at most max characters, starting at the cursor position of the window.
+    ///
+    /// Returns the line when Enter is pressed, or None if reading it is cancelled with Esc or
+    /// reading the input fails. A line that isn't empty is added to the history, unless it's the
+    /// same as the newest line in it.
+    ///
+    /// The input is read with blocking reads, whatever the timeout of the window is, and the
+    /// timeout is restored afterwards.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, noecho, Input, LineEditor};
+    ///
+    /// let window = initscr();
+    /// noecho().unwrap();
+    /// window.nodelay(true).unwrap();
+    /// window.ungetch(&Input::Character('\n')).unwrap();
+    /// window.ungetch(&Input::Character('a')).unwrap();
+    /// assert_eq!(Some("a".to_string()), LineEditor::new().read_line(&window, 80));
+    /// // Back to not waiting for input
+    /// assert_eq!(None, window.getch());
+    /// endwin();
+    /// ```
+    pub fn read_line(&mut self, window: &Window, max: usize) -> Option<String> {
+        let timeout = window.get_timeout();
+        window.timeout(-1);
+        let line = self.edit(window, max);
+        window.timeout(timeout);
+        line
+    }
+
+    fn edit(&mut self, window: &Window, max: usize) -> Option<String> {
+        let (y, x) = window.get_cur_yx();
+        let width = (window.get_max_x() - x - 1).max(1) as usize;
+        let mut line = Line {
+            chars: Vec::new(),
+            cursor: 0,
+            insert: true,
+        };
+        // The position in the history while moving through it, and the line that was being
+        // edited before that
+        let mut history_index = self.history.len();
+        let mut edited = String::new();
+
+        loop {
+            line.draw(window, y, x, width);
+            // A blocking read only returns nothing if reading fails
+            let input = window.getch()?;
+            match input {
+                Input::Character('\n') | Input::Character('\r') | Input::KeyEnter => break,
+                Input::KeyEscape | Input::Character('\x1b') => return None,
+                Input::KeyLeft => line.cursor = line.cursor.saturating_sub(1),
+                Input::KeyRight => line.cursor = (line.cursor + 1).min(line.chars.len()),
+                Input::KeyHome | Input::Character('\x01') => line.cursor = 0,
+                Input::KeyEnd | Input::Character('\x05') => line.cursor = line.chars.len(),
+                Input::KeyBackspace | Input::Character('\x08') | Input::Character('\x7f')
+                    if line.cursor > 0 =>
+                {
+                    line.cursor -= 1;
+                    line.chars.remove(line.cursor);
+                }
+                Input::KeyDC if line.cursor < line.chars.len() => {
+                    line.chars.remove(line.cursor);
+                }
+                Input::Character('\x17') => line.delete_word(),
+                Input::Character('\x15') => {
+                    line.chars.drain(..line.cursor);
+                    line.cursor = 0;
+                }
+                Input::KeyIC => line.insert = !line.insert,
+                Input::KeyUp if history_index > 0 => {
+                    if history_index == self.history.len() {
+                        edited = line.text();
+                    }
+                    history_index -= 1;
+                    line.set(&self.history[history_index], max);
+                }
+                Input::KeyDown if history_index < self.history.len() => {
+                    history_index += 1;
+                    match self.history.get(history_index) {
+                        Some(entry) => line.set(entry, max),
+                        None => line.set(&edited, max),
+                    }
+                }
+                Input::Character('\t') => {
+                    let completion = self.completer.as_mut().and_then(|f| f(&line.text()));
+                    if let Some(completion) = completion {
+                        line.set(&completion, max);
+                    }
+                }
+                Input::Character(c) if !c.is_control() => line.put(c, max),
+                Input::Paste(ref text) => {
+                    for c in pasted_chars(text) {
+                        line.put(c, max);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let text = line.text();
+        if !text.is_empty() && self.history.last() != Some(&text) {
+            self.history.push(text.clone());
+        }
+        Some(text)
+    }
+}
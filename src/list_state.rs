@@ -0,0 +1,479 @@
+use crate::{chtype, display_width, Input, Result, Window};
+
+/// An item that a `ListState` moves through, selects and searches.
+pub(crate) trait Item {
+    /// The text that type-ahead search looks in.
+    fn search_text(&self) -> &str;
+
+    /// Whether the item can be moved to, chosen and toggled.
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    fn selected(&self) -> bool;
+
+    fn set_selected(&mut self, selected: bool);
+}
+
+/// What a `ListState` did with the input it was given, which the widgets report as their own
+/// events.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Change {
+    Moved(usize),
+    Selected(usize),
+    Toggled(usize, bool),
+}
+
+/// How the text typed for type-ahead search has to appear in the search text of an item, given
+/// both in lowercase.
+pub(crate) type Matcher = fn(&str, &str) -> bool;
+
+/// The state that `Menu` and `ListView` share: the current item, the first item shown, whether
+/// several items can be selected and the text typed to search for an item. The items themselves
+/// are kept by the widget and passed in.
+#[derive(Debug, Default)]
+pub(crate) struct ListState {
+    current: usize,
+    top: usize,
+    multi_select: bool,
+    search: String,
+}
+
+impl ListState {
+    /// Starts out on the first enabled item.
+    pub(crate) fn new<T: Item>(items: &[T]) -> ListState {
+        ListState {
+            current: find_enabled(items, 0, true).unwrap_or(0),
+            ..ListState::default()
+        }
+    }
+
+    pub(crate) fn current(&self, count: usize) -> Option<usize> {
+        if count == 0 {
+            None
+        } else {
+            Some(self.current)
+        }
+    }
+
+    /// Makes the item the current item without reporting a move, and forgets the search.
+    pub(crate) fn set_current(&mut self, count: usize, index: usize) {
+        if index < count {
+            self.current = index;
+            self.search.clear();
+        }
+    }
+
+    /// Keeps the current item within a new number of items.
+    pub(crate) fn set_count(&mut self, count: usize) {
+        self.current = self.current.min(count.saturating_sub(1));
+        self.search.clear();
+    }
+
+    /// The index of the first item shown.
+    pub(crate) fn top(&self) -> usize {
+        self.top
+    }
+
+    pub(crate) fn search(&self) -> &str {
+        &self.search
+    }
+
+    pub(crate) fn multi_select(&self) -> bool {
+        self.multi_select
+    }
+
+    pub(crate) fn set_multi_select<T: Item>(&mut self, items: &mut [T], multi_select: bool) {
+        self.multi_select = multi_select;
+        if !multi_select {
+            for item in items {
+                item.set_selected(false);
+            }
+        }
+    }
+
+    pub(crate) fn selected_items<T: Item>(&self, items: &[T]) -> Vec<usize> {
+        (0..items.len()).filter(|&i| items[i].selected()).collect()
+    }
+
+    /// The check box shown in front of an item in a multi-select list.
+    pub(crate) fn check_box<T: Item>(&self, item: &T) -> &'static str {
+        match (self.multi_select, item.selected()) {
+            (false, _) => "",
+            (true, false) => "[ ] ",
+            (true, true) => "[x] ",
+        }
+    }
+
+    /// Scrolls as little as possible to show the current item in the given number of rows.
+    pub(crate) fn scroll_to_current(&mut self, count: usize, rows: usize) {
+        let rows = rows.max(1);
+        if self.current < self.top {
+            self.top = self.current;
+        } else if self.current >= self.top + rows {
+            self.top = self.current + 1 - rows;
+        }
+        self.top = self.top.min(count.saturating_sub(rows));
+    }
+
+    /// Handles the keys for moving, choosing, toggling and searching. Page Up and Page Down move
+    /// by the given number of items.
+    pub(crate) fn handle_key<T: Item>(
+        &mut self,
+        items: &mut [T],
+        input: &Input,
+        page: usize,
+        matches: Matcher,
+    ) -> Option<Change> {
+        let page = page.max(1) as isize;
+        match *input {
+            Input::KeyUp => self.move_by(items, -1),
+            Input::KeyDown => self.move_by(items, 1),
+            Input::KeyPPage => self.move_by(items, -page),
+            Input::KeyNPage => self.move_by(items, page),
+            Input::KeyHome => find_enabled(items, 0, true).and_then(|i| self.move_to(items, i)),
+            Input::KeyEnd => {
+                let last = items.len().saturating_sub(1);
+                find_enabled(items, last, false).and_then(|i| self.move_to(items, i))
+            }
+            Input::KeyEnter | Input::Character('\n') | Input::Character('\r') => self.select(items),
+            Input::Character(' ') if self.multi_select => self.toggle(items),
+            Input::KeyBackspace | Input::Character('\x08') | Input::Character('\x7f') => {
+                self.search.pop();
+                None
+            }
+            Input::KeyEscape | Input::Character('\x1b') => {
+                self.search.clear();
+                None
+            }
+            Input::Character(c) if !c.is_control() => self.extend_search(items, c, matches),
+            _ => None,
+        }
+    }
+
+    /// Moves by the given number of items, to the nearest enabled item in that direction, or in
+    /// the other direction if there is none.
+    pub(crate) fn move_by<T: Item>(&mut self, items: &[T], delta: isize) -> Option<Change> {
+        if items.is_empty() {
+            return None;
+        }
+        let last = items.len() as isize - 1;
+        let target = (self.current as isize + delta).max(0).min(last) as usize;
+        let index = find_enabled(items, target, delta > 0)
+            .or_else(|| find_enabled(items, target, delta <= 0));
+        index.and_then(|index| self.move_to(items, index))
+    }
+
+    pub(crate) fn move_to<T: Item>(&mut self, items: &[T], index: usize) -> Option<Change> {
+        self.search.clear();
+        if index == self.current || index >= items.len() {
+            None
+        } else {
+            self.current = index;
+            Some(Change::Moved(index))
+        }
+    }
+
+    pub(crate) fn select<T: Item>(&self, items: &[T]) -> Option<Change> {
+        match items.get(self.current) {
+            Some(item) if item.enabled() => Some(Change::Selected(self.current)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn toggle<T: Item>(&mut self, items: &mut [T]) -> Option<Change> {
+        match items.get_mut(self.current) {
+            Some(item) if item.enabled() => {
+                let selected = !item.selected();
+                item.set_selected(selected);
+                Some(Change::Toggled(self.current, selected))
+            }
+            _ => None,
+        }
+    }
+
+    /// Adds the character to the search and moves to the next enabled item that matches, starting
+    /// from the current item. The character is dropped if no item matches.
+    fn extend_search<T: Item>(&mut self, items: &[T], c: char, matches: Matcher) -> Option<Change> {
+        self.search.push(c);
+        let search = self.search.to_lowercase();
+        let count = items.len();
+        let found = (0..count).map(|i| (self.current + i) % count).find(|&i| {
+            let item = &items[i];
+            item.enabled() && matches(&item.search_text().to_lowercase(), &search)
+        });
+        match found {
+            Some(index) if index != self.current => {
+                self.current = index;
+                Some(Change::Moved(index))
+            }
+            Some(_) => None,
+            None => {
+                self.search.pop();
+                None
+            }
+        }
+    }
+}
+
+/// Returns the first enabled item starting from the given index, going forward or backward.
+fn find_enabled<T: Item>(items: &[T], from: usize, forward: bool) -> Option<usize> {
+    if forward {
+        (from..items.len()).find(|&i| items[i].enabled())
+    } else {
+        (0..=from.min(items.len().saturating_sub(1)))
+            .rev()
+            .find(|&i| items.get(i).is_some_and(Item::enabled))
+    }
+}
+
+/// Draws a line at the cursor, padded to the width so that the attributes fill it.
+pub(crate) fn draw_line(
+    window: &Window,
+    mut line: String,
+    attributes: chtype,
+    cols: i32,
+    last: bool,
+) -> Result<()> {
+    let padding = (cols as usize).saturating_sub(display_width(&line));
+    line.push_str(&" ".repeat(padding));
+    window.attron(attributes)?;
+    let result = window.addstr_cols(&line, cols as usize, Some("…"));
+    window.attroff(attributes)?;
+    // Writing the bottom right corner of a window that can't scroll is reported as an error even
+    // though the character is written
+    if last {
+        return Ok(());
+    }
+    result.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestItem {
+        text: &'static str,
+        enabled: bool,
+        selected: bool,
+    }
+
+    impl Item for TestItem {
+        fn search_text(&self) -> &str {
+            self.text
+        }
+
+        fn enabled(&self) -> bool {
+            self.enabled
+        }
+
+        fn selected(&self) -> bool {
+            self.selected
+        }
+
+        fn set_selected(&mut self, selected: bool) {
+            self.selected = selected;
+        }
+    }
+
+    fn items(texts: &[&'static str]) -> Vec<TestItem> {
+        texts
+            .iter()
+            .map(|&text| TestItem {
+                text,
+                enabled: true,
+                selected: false,
+            })
+            .collect()
+    }
+
+    /// Hands the input to the state with a page of 3 items, searching like `ListView`.
+    fn key(state: &mut ListState, items: &mut [TestItem], input: &Input) -> Option<Change> {
+        state.handle_key(items, input, 3, |text, search| text.contains(search))
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut items = items(&["a", "b", "c", "d", "e", "f"]);
+        let mut state = ListState::new(&items);
+        assert_eq!(Some(0), state.current(items.len()));
+        assert_eq!(None, key(&mut state, &mut items, &Input::KeyUp));
+        assert_eq!(
+            Some(Change::Moved(1)),
+            key(&mut state, &mut items, &Input::KeyDown)
+        );
+        assert_eq!(
+            Some(Change::Moved(4)),
+            key(&mut state, &mut items, &Input::KeyNPage)
+        );
+        // Paging stops at the last item
+        assert_eq!(
+            Some(Change::Moved(5)),
+            key(&mut state, &mut items, &Input::KeyNPage)
+        );
+        assert_eq!(None, key(&mut state, &mut items, &Input::KeyDown));
+        assert_eq!(
+            Some(Change::Moved(2)),
+            key(&mut state, &mut items, &Input::KeyPPage)
+        );
+        assert_eq!(
+            Some(Change::Moved(0)),
+            key(&mut state, &mut items, &Input::KeyHome)
+        );
+        assert_eq!(
+            Some(Change::Moved(5)),
+            key(&mut state, &mut items, &Input::KeyEnd)
+        );
+        let enter = Input::Character('\n');
+        assert_eq!(
+            Some(Change::Selected(5)),
+            key(&mut state, &mut items, &enter)
+        );
+    }
+
+    #[test]
+    fn test_disabled_items() {
+        let mut items = items(&["a", "b", "c", "d", "e"]);
+        items[0].enabled = false;
+        items[2].enabled = false;
+        items[4].enabled = false;
+        let mut state = ListState::new(&items);
+        assert_eq!(Some(1), state.current(items.len()));
+        // Disabled items are skipped, and moving past the last enabled one goes back to it
+        assert_eq!(
+            Some(Change::Moved(3)),
+            key(&mut state, &mut items, &Input::KeyDown)
+        );
+        assert_eq!(None, key(&mut state, &mut items, &Input::KeyDown));
+        assert_eq!(None, key(&mut state, &mut items, &Input::KeyEnd));
+        assert_eq!(
+            Some(Change::Moved(1)),
+            key(&mut state, &mut items, &Input::KeyHome)
+        );
+        assert_eq!(None, key(&mut state, &mut items, &Input::Character('e')));
+        // A disabled current item can't be chosen
+        state.set_current(items.len(), 2);
+        assert_eq!(None, key(&mut state, &mut items, &Input::KeyEnter));
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut items = items(&[]);
+        let mut state = ListState::new(&items);
+        assert_eq!(None, state.current(0));
+        for input in &[
+            Input::KeyDown,
+            Input::KeyEnd,
+            Input::KeyEnter,
+            Input::Character('a'),
+        ] {
+            assert_eq!(None, key(&mut state, &mut items, input));
+        }
+        state.scroll_to_current(0, 3);
+        assert_eq!(0, state.top());
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut items = items(&["a", "b"]);
+        let mut state = ListState::new(&items);
+        assert_eq!("", state.check_box(&items[0]));
+        state.set_multi_select(&mut items, true);
+        assert_eq!("[ ] ", state.check_box(&items[0]));
+        let space = Input::Character(' ');
+        assert_eq!(
+            Some(Change::Toggled(0, true)),
+            key(&mut state, &mut items, &space)
+        );
+        assert_eq!("[x] ", state.check_box(&items[0]));
+        key(&mut state, &mut items, &Input::KeyDown);
+        key(&mut state, &mut items, &space);
+        assert_eq!(vec![0, 1], state.selected_items(&items));
+        assert_eq!(
+            Some(Change::Toggled(1, false)),
+            key(&mut state, &mut items, &space)
+        );
+        // Turning multi-select off deselects everything
+        state.set_multi_select(&mut items, false);
+        assert!(state.selected_items(&items).is_empty());
+    }
+
+    #[test]
+    fn test_scrolling() {
+        let mut state = ListState::default();
+        state.set_current(10, 7);
+        state.scroll_to_current(10, 3);
+        assert_eq!(5, state.top());
+        // Moving within the rows shown doesn't scroll
+        state.set_current(10, 6);
+        state.scroll_to_current(10, 3);
+        assert_eq!(5, state.top());
+        state.set_current(10, 2);
+        state.scroll_to_current(10, 3);
+        assert_eq!(2, state.top());
+        // No rows are left empty after the last item while there are items before the first row
+        state.scroll_to_current(10, 20);
+        assert_eq!(0, state.top());
+        state.set_current(10, 9);
+        state.scroll_to_current(10, 4);
+        assert_eq!(6, state.top());
+        state.set_count(6);
+        assert_eq!(Some(5), state.current(6));
+        state.scroll_to_current(6, 4);
+        assert_eq!(2, state.top());
+    }
+
+    #[test]
+    fn test_search() {
+        let mut items = items(&["Apple", "Banana", "Blueberry", "Cherry"]);
+        let mut state = ListState::new(&items);
+        let type_prefix = |state: &mut ListState, items: &mut [TestItem], c| {
+            state.handle_key(items, &Input::Character(c), 1, |text, search| {
+                text.starts_with(search)
+            })
+        };
+        assert_eq!(
+            Some(Change::Moved(1)),
+            type_prefix(&mut state, &mut items, 'b')
+        );
+        assert_eq!(
+            Some(Change::Moved(2)),
+            type_prefix(&mut state, &mut items, 'L')
+        );
+        assert_eq!("bL", state.search());
+        // Characters that don't match anything are dropped
+        assert_eq!(None, type_prefix(&mut state, &mut items, 'x'));
+        assert_eq!("bL", state.search());
+        key(&mut state, &mut items, &Input::KeyBackspace);
+        assert_eq!("b", state.search());
+        key(&mut state, &mut items, &Input::KeyEscape);
+        assert_eq!("", state.search());
+        // An escape character that getch() doesn't decode clears it as well
+        type_prefix(&mut state, &mut items, 'c');
+        assert_eq!("c", state.search());
+        key(&mut state, &mut items, &Input::Character('\x1b'));
+        assert_eq!("", state.search());
+
+        // The search starts at the current item and wraps around
+        assert_eq!(
+            Some(Change::Moved(0)),
+            key(&mut state, &mut items, &Input::KeyHome)
+        );
+        assert_eq!(
+            Some(Change::Moved(2)),
+            key(&mut state, &mut items, &Input::Character('r'))
+        );
+        assert_eq!(None, key(&mut state, &mut items, &Input::Character('r')));
+        assert_eq!("rr", state.search());
+        // Moving forgets the search
+        assert_eq!(
+            Some(Change::Moved(3)),
+            key(&mut state, &mut items, &Input::KeyDown)
+        );
+        assert_eq!("", state.search());
+        assert_eq!(
+            Some(Change::Moved(1)),
+            key(&mut state, &mut items, &Input::Character('n'))
+        );
+    }
+}
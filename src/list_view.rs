@@ -0,0 +1,362 @@
+use crate::list_state::{draw_line, Change, Item, ListState};
+use crate::{chtype, Attributes, ColorPair, Input, Pad, Rect, Result, Window};
+use crate::{MouseButton, MouseEvent, MouseEventKind, A_REVERSE};
+
+/// An item of a `ListView`, with the text shown for it and how it's styled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListItem {
+    text: String,
+    attributes: Attributes,
+    selected: bool,
+}
+
+impl ListItem {
+    /// Creates an item shown in the attributes of the window.
+    pub fn new<T: Into<String>>(text: T) -> ListItem {
+        ListItem {
+            text: text.into(),
+            attributes: Attributes::new(),
+            selected: false,
+        }
+    }
+
+    /// Sets the attributes the item is shown in, which can include a color pair.
+    pub fn with_attributes<A: Into<Attributes>>(mut self, attributes: A) -> ListItem {
+        self.attributes = attributes.into();
+        self
+    }
+
+    /// Sets the color pair the item is shown in, keeping its other attributes.
+    pub fn with_color_pair(mut self, color_pair: ColorPair) -> ListItem {
+        self.attributes.set_color_pair(color_pair);
+        self
+    }
+
+    /// The text of the item, which is what type-ahead search looks in.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The attributes the item is shown in.
+    pub fn attributes(&self) -> Attributes {
+        self.attributes
+    }
+
+    /// Returns true if the item is selected in a multi-select list.
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+}
+
+impl Item for ListItem {
+    fn search_text(&self) -> &str {
+        &self.text
+    }
+
+    fn selected(&self) -> bool {
+        self.selected
+    }
+
+    fn set_selected(&mut self, selected: bool) {
+        self.selected = selected;
+    }
+}
+
+/// What happened to a `ListView` as a result of the input it was given.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ListEvent {
+    /// The current item changed to the item with the given index.
+    Moved(usize),
+    /// The item with the given index was chosen with Enter or a double click.
+    Selected(usize),
+    /// The item with the given index was selected (true) or deselected (false) in a multi-select
+    /// list, with space or a click.
+    Toggled(usize, bool),
+}
+
+impl ListEvent {
+    fn from_change(change: Change) -> ListEvent {
+        match change {
+            Change::Moved(index) => ListEvent::Moved(index),
+            Change::Selected(index) => ListEvent::Selected(index),
+            Change::Toggled(index, selected) => ListEvent::Toggled(index, selected),
+        }
+    }
+}
+
+/// A scrollable list of styled items, which can be moved through and selected from the keyboard
+/// and the mouse.
+///
+/// The list draws itself into a window with draw(), or into a pad with draw_pad(), and keeps
+/// track of the rectangle of the screen it was last drawn to, scrolling to keep the current item
+/// visible. Input read with getch() is handed to handle_input(), which reports what it did as a
+/// `ListEvent`:
+///
+/// - The arrow keys, Home, End, Page Up and Page Down move the current item.
+/// - Enter or a double click chooses the current item, reported as `ListEvent::Selected`.
+/// - A click moves to the item clicked, and the scroll wheel moves the current item.
+/// - In a multi-select list space or a click toggles the current item.
+/// - Typing characters searches for the next item that contains them, ignoring case. Backspace
+///   removes the last character of the search, Escape the whole search.
+///
+/// Each item is shown in its own attributes and color pair, combined with the highlight for the
+/// current item.
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, Attribute, ColorPair, Input, ListEvent, ListItem, ListView};
+/// use pancurses::{Modifiers, MouseButton, MouseEvent, MouseEventKind};
+///
+/// let window = initscr();
+/// let mut list = ListView::new(vec![
+///     ListItem::new("Cargo.toml").with_attributes(Attribute::Bold),
+///     ListItem::new("README.md").with_color_pair(ColorPair(1)),
+///     ListItem::new("src/lib.rs"),
+///     ListItem::new("src/window.rs"),
+/// ]);
+/// let subwindow = window.derwin(2, 20, 0, 0).unwrap();
+/// list.draw(&subwindow).unwrap();
+///
+/// assert_eq!(Some(ListEvent::Moved(1)), list.handle_input(&Input::KeyDown));
+/// assert_eq!(Some(ListEvent::Moved(3)), list.handle_input(&Input::KeyEnd));
+/// // Type-ahead search finds the text anywhere in an item
+/// assert_eq!(Some(ListEvent::Moved(0)), list.handle_input(&Input::Character('l')));
+/// assert_eq!(Some(ListEvent::Moved(2)), list.handle_input(&Input::Character('i')));
+/// assert_eq!("li", list.search());
+/// assert_eq!(Some(ListEvent::Selected(2)), list.handle_input(&Input::Character('\n')));
+///
+/// // Clicking the first line of the window, which has scrolled to show the current item
+/// list.draw(&subwindow).unwrap();
+/// let click = MouseEvent {
+///     kind: MouseEventKind::Clicked,
+///     button: Some(MouseButton::Left),
+///     modifiers: Modifiers::NONE,
+///     position: (0, 0),
+/// };
+/// assert_eq!(Some(ListEvent::Moved(1)), list.handle_input(&Input::Mouse(click)));
+/// endwin();
+/// ```
+#[derive(Debug)]
+pub struct ListView {
+    items: Vec<ListItem>,
+    state: ListState,
+    highlight: chtype,
+    /// Where the list was last drawn on the screen.
+    area: Rect,
+}
+
+impl ListView {
+    /// Creates a single-select list with the first item as the current item.
+    pub fn new(items: Vec<ListItem>) -> ListView {
+        ListView {
+            state: ListState::new(&items),
+            items,
+            highlight: A_REVERSE,
+            area: Rect::default(),
+        }
+    }
+
+    /// The index of the current item, or None if the list has no items.
+    pub fn current(&self) -> Option<usize> {
+        self.state.current(self.items.len())
+    }
+
+    /// Makes the item with the given index the current item.
+    pub fn set_current(&mut self, index: usize) {
+        self.state.set_current(self.items.len(), index);
+    }
+
+    /// The items of the list.
+    pub fn items(&self) -> &[ListItem] {
+        &self.items
+    }
+
+    /// Replaces the items of the list, keeping the current item at the same index if there still
+    /// is one.
+    pub fn set_items(&mut self, items: Vec<ListItem>) {
+        self.items = items;
+        self.state.set_count(self.items.len());
+    }
+
+    /// The indices of the items that are checked, in the order they appear in the list.
+    pub fn selected_items(&self) -> Vec<usize> {
+        self.state.selected_items(&self.items)
+    }
+
+    /// Selects or deselects the item with the given index in a multi-select list.
+    pub fn set_selected(&mut self, index: usize, selected: bool) {
+        if let Some(item) = self.items.get_mut(index) {
+            item.selected = selected;
+        }
+    }
+
+    /// Turns multi-select on or off. While it is on each item is drawn with a check box that
+    /// space and clicks toggle, and turning it off clears every check box.
+    pub fn set_multi_select(&mut self, multi_select: bool) {
+        self.state.set_multi_select(&mut self.items, multi_select);
+    }
+
+    /// Sets the attributes added to those of the current item, `A_REVERSE` by default.
+    pub fn set_highlight(&mut self, attributes: chtype) {
+        self.highlight = attributes;
+    }
+
+    /// The characters typed so far to search for an item.
+    pub fn search(&self) -> &str {
+        self.state.search()
+    }
+
+    /// Draws the visible items into the window, starting from its upper left corner.
+    pub fn draw(&mut self, window: &Window) -> Result<()> {
+        let (rows, cols) = window.get_max_yx();
+        let (begy, begx) = window.get_beg_yx();
+        self.area = Rect::new(rows, cols, begy, begx);
+        self.state
+            .scroll_to_current(self.items.len(), rows as usize);
+
+        for row in 0..rows {
+            window.mv(row, 0)?;
+            window.clrtoeol()?;
+            let index = self.state.top() + row as usize;
+            if index < self.items.len() {
+                self.draw_item(window, index, cols, row == rows - 1)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws all the items into the pad, one per line, and copies the part of the pad that shows
+    /// the current item to the area of the screen, without updating the physical screen.
+    ///
+    /// The pad needs a line for each item. Drawing to a pad takes longer than drawing just the
+    /// visible items into a window, but the pad can also be shown elsewhere with its own refresh.
+    ///
+    /// ```rust
+    /// use pancurses::{doupdate, endwin, initscr, newpad, ListItem, ListView, Rect};
+    /// use pancurses::{A_CHARTEXT, A_REVERSE};
+    ///
+    /// let window = initscr();
+    /// let items: Vec<_> = (0..100).map(|i| ListItem::new(format!("Item {}", i))).collect();
+    /// let pad = newpad(items.len() as i32, 20).unwrap();
+    /// let mut list = ListView::new(items);
+    /// list.set_current(50);
+    /// window.refresh().unwrap();
+    /// list.draw_pad(&pad, Rect::new(5, 20, 2, 0)).unwrap();
+    /// doupdate().unwrap();
+    /// assert_eq!('5', (pad.mvinch(50, 5) & A_CHARTEXT) as u8 as char);
+    /// assert_eq!(A_REVERSE, pad.mvinch(50, 0) & A_REVERSE);
+    /// endwin();
+    /// ```
+    pub fn draw_pad(&mut self, pad: &Pad, area: Rect) -> Result<()> {
+        let (rows, cols) = pad.get_max_yx();
+        self.area = area;
+        self.state
+            .scroll_to_current(self.items.len(), area.nlines as usize);
+
+        pad.erase()?;
+        for index in 0..self.items.len().min(rows as usize) {
+            pad.mv(index as i32, 0)?;
+            self.draw_item(pad.window(), index, cols, index == rows as usize - 1)?;
+        }
+        pad.noutrefresh(
+            self.state.top() as i32,
+            0,
+            area.begy,
+            area.begx,
+            area.begy + area.nlines - 1,
+            area.begx + area.ncols - 1,
+        )
+    }
+
+    /// Draws the item at the cursor in its own attributes, adding the highlight if it's current.
+    fn draw_item(&self, window: &Window, index: usize, cols: i32, last: bool) -> Result<()> {
+        let item = &self.items[index];
+        let mut line = self.state.check_box(item).to_string();
+        line.push_str(&item.text);
+
+        let mut attributes = chtype::from(item.attributes);
+        if self.state.current(self.items.len()) == Some(index) {
+            attributes |= self.highlight;
+        }
+        draw_line(window, line, attributes, cols, last)
+    }
+
+    /// Passes a key or mouse event on to the list. The result says whether the current item
+    /// moved, was chosen or was toggled, and is None when nothing happened.
+    ///
+    /// Page Up and Page Down move by the height the list was last drawn with, and `Input::Mouse`
+    /// is handled by handle_mouse().
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, Input, ListEvent, ListItem, ListView};
+    ///
+    /// let window = initscr();
+    /// window.keypad(true).unwrap();
+    /// let mut list = ListView::new(vec![ListItem::new("alpha"), ListItem::new("beta")]);
+    /// assert_eq!(Some(ListEvent::Moved(1)), list.handle_input(&Input::Character('b')));
+    /// assert_eq!("b", list.search());
+    /// // Escape clears the search
+    /// window.ungetch(&Input::KeyEscape).unwrap();
+    /// let input = window.getch().unwrap();
+    /// assert_eq!(None, list.handle_input(&input));
+    /// assert_eq!("", list.search());
+    /// endwin();
+    /// ```
+    pub fn handle_input(&mut self, input: &Input) -> Option<ListEvent> {
+        match *input {
+            Input::Mouse(ref event) => self.handle_mouse(event),
+            _ => {
+                let page = self.area.nlines.max(1) as usize;
+                self.state
+                    .handle_key(&mut self.items, input, page, |text, search| {
+                        text.contains(search)
+                    })
+                    .map(ListEvent::from_change)
+            }
+        }
+    }
+
+    /// Handles a mouse event, using the rectangle of the screen the list was last drawn to.
+    ///
+    /// A click on an item moves to it, or toggles it in a multi-select list, and a double click
+    /// chooses it. The scroll wheel moves the current item. Events outside of the list are
+    /// ignored.
+    pub fn handle_mouse(&mut self, event: &MouseEvent) -> Option<ListEvent> {
+        let (y, x) = event.position;
+        let area = self.area;
+        if y < area.begy
+            || y >= area.begy + area.nlines
+            || x < area.begx
+            || x >= area.begx + area.ncols
+        {
+            return None;
+        }
+        let change = match (event.kind, event.button) {
+            (MouseEventKind::WheelUp, _) => self.state.move_by(&self.items, -1),
+            (MouseEventKind::WheelDown, _) => self.state.move_by(&self.items, 1),
+            (kind, Some(MouseButton::Left)) => {
+                let index = self.state.top() + (y - area.begy) as usize;
+                if index >= self.items.len() {
+                    return None;
+                }
+                match kind {
+                    MouseEventKind::DoubleClicked => {
+                        self.state.set_current(self.items.len(), index);
+                        self.state.select(&self.items)
+                    }
+                    MouseEventKind::Clicked | MouseEventKind::Pressed
+                        if self.state.multi_select() =>
+                    {
+                        self.state.set_current(self.items.len(), index);
+                        self.state.toggle(&mut self.items)
+                    }
+                    MouseEventKind::Clicked | MouseEventKind::Pressed => {
+                        self.state.move_to(&self.items, index)
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        change.map(ListEvent::from_change)
+    }
+}
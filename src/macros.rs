@@ -0,0 +1,49 @@
+/// Formats the arguments like `format!` and adds the result to the window at the current cursor
+/// position.
+///
+/// This is the safe replacement for C's `wprintw()`: the formatting is done by Rust, so `%`
+/// sequences in the arguments are written out as they are.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate pancurses;
+///
+/// use pancurses::{endwin, initscr};
+///
+/// fn main() {
+///     let window = initscr();
+///     let file_name = "100%s.txt";
+///     wprintw!(window, "Opening {}...", file_name).unwrap();
+///     endwin();
+/// }
+/// ```
+#[macro_export]
+macro_rules! wprintw {
+    ($window:expr, $($arg:tt)*) => {
+        $window.printw(::std::fmt::format(format_args!($($arg)*)))
+    };
+}
+
+/// Moves the cursor to the given position, formats the arguments like `format!` and adds the
+/// result to the window.
+///
+/// This is the safe replacement for C's `mvwprintw()`, see `wprintw!`.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate pancurses;
+///
+/// use pancurses::{endwin, initscr};
+///
+/// fn main() {
+///     let window = initscr();
+///     mvwprintw!(window, 1, 2, "{} of {} done", 3, 10).unwrap();
+///     endwin();
+/// }
+/// ```
+#[macro_export]
+macro_rules! mvwprintw {
+    ($window:expr, $y:expr, $x:expr, $($arg:tt)*) => {
+        $window.mvprintw($y, $x, ::std::fmt::format(format_args!($($arg)*)))
+    };
+}
@@ -0,0 +1,339 @@
+use crate::list_state::{draw_line, Change, Item, ListState};
+use crate::{chtype, display_width, Input, Result, Window, A_DIM, A_REVERSE};
+use crate::{MouseButton, MouseEvent, MouseEventKind};
+
+/// An item of a `Menu`, with a name and an optional description.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MenuItem {
+    name: String,
+    description: String,
+    enabled: bool,
+    selected: bool,
+}
+
+impl MenuItem {
+    /// Creates an enabled item. Use an empty description for an item without one.
+    pub fn new<N: Into<String>, D: Into<String>>(name: N, description: D) -> MenuItem {
+        MenuItem {
+            name: name.into(),
+            description: description.into(),
+            enabled: true,
+            selected: false,
+        }
+    }
+
+    /// The name of the item, which is what the pattern of the menu is matched against.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The description shown next to the name.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns false if the item has been disabled, in which case it can't be moved to or selected.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns true if the item is selected in a multi-select menu.
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+}
+
+impl Item for MenuItem {
+    fn search_text(&self) -> &str {
+        &self.name
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn selected(&self) -> bool {
+        self.selected
+    }
+
+    fn set_selected(&mut self, selected: bool) {
+        self.selected = selected;
+    }
+}
+
+/// What happened to a `Menu` as a result of the input it was given.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MenuEvent {
+    /// The current item changed to the item with the given index.
+    Moved(usize),
+    /// The item with the given index was chosen with Enter or a mouse click.
+    Selected(usize),
+    /// The item with the given index was selected (true) or deselected (false) in a multi-select
+    /// menu, with space or a mouse click.
+    Toggled(usize, bool),
+}
+
+impl MenuEvent {
+    fn from_change(change: Change) -> MenuEvent {
+        match change {
+            Change::Moved(index) => MenuEvent::Moved(index),
+            Change::Selected(index) => MenuEvent::Selected(index),
+            Change::Toggled(index, selected) => MenuEvent::Toggled(index, selected),
+        }
+    }
+}
+
+/// A list of items that can be moved through and selected from the keyboard and the mouse.
+///
+/// The menu draws itself into a window with draw(), using as many lines and columns as the window
+/// has and scrolling to keep the current item visible. Input read with getch() is handed to
+/// handle_input(), which reports what it did as a `MenuEvent`:
+///
+/// - The arrow keys, Home, End, Page Up and Page Down move between the enabled items.
+/// - Enter or a click chooses the current item, reported as `MenuEvent::Selected`.
+/// - In a multi-select menu space or a click toggles the current item instead.
+/// - Typing a character jumps to the next item whose name starts with the characters typed so
+///   far, ignoring case. Backspace removes the last character of the pattern, Escape the whole
+///   pattern.
+///
+/// The menu is implemented in Rust on top of `Window`, so it works the same way on all platforms.
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, Input, Menu, MenuEvent, MenuItem};
+/// use pancurses::{Modifiers, MouseButton, MouseEvent, MouseEventKind};
+///
+/// let window = initscr();
+/// let mut menu = Menu::new(vec![
+///     MenuItem::new("Apple", "A red fruit"),
+///     MenuItem::new("Banana", "A yellow fruit"),
+///     MenuItem::new("Blueberry", "A blue fruit"),
+///     MenuItem::new("Cherry", "Another red fruit"),
+/// ]);
+/// menu.set_enabled(2, false);
+///
+/// assert_eq!(Some(MenuEvent::Moved(1)), menu.handle_input(&window, &Input::KeyDown));
+/// // The disabled item is skipped
+/// assert_eq!(Some(MenuEvent::Moved(3)), menu.handle_input(&window, &Input::KeyDown));
+/// assert_eq!(Some(MenuEvent::Moved(0)), menu.handle_input(&window, &Input::Character('a')));
+/// assert_eq!(Some(MenuEvent::Selected(0)), menu.handle_input(&window, &Input::Character('\n')));
+///
+/// // Clicking the second line of the window chooses the second item
+/// let click = MouseEvent {
+///     kind: MouseEventKind::Clicked,
+///     button: Some(MouseButton::Left),
+///     modifiers: Modifiers::NONE,
+///     position: (1, 0),
+/// };
+/// assert_eq!(Some(MenuEvent::Selected(1)), menu.handle_input(&window, &Input::Mouse(click)));
+///
+/// menu.draw(&window).unwrap();
+/// window.refresh().unwrap();
+/// endwin();
+/// ```
+#[derive(Debug)]
+pub struct Menu {
+    items: Vec<MenuItem>,
+    state: ListState,
+    show_descriptions: bool,
+    highlight: chtype,
+    disabled: chtype,
+}
+
+impl Menu {
+    /// Creates a single-select menu with the first enabled item as the current item.
+    pub fn new(items: Vec<MenuItem>) -> Menu {
+        Menu {
+            state: ListState::new(&items),
+            items,
+            show_descriptions: true,
+            highlight: A_REVERSE,
+            disabled: A_DIM,
+        }
+    }
+
+    /// The index of the current item, or None if the menu has no items.
+    pub fn current(&self) -> Option<usize> {
+        self.state.current(self.items.len())
+    }
+
+    /// Makes the item with the given index the current item.
+    pub fn set_current(&mut self, index: usize) {
+        self.state.set_current(self.items.len(), index);
+    }
+
+    /// The items of the menu.
+    pub fn items(&self) -> &[MenuItem] {
+        &self.items
+    }
+
+    /// The indices of the selected items of a multi-select menu.
+    pub fn selected_items(&self) -> Vec<usize> {
+        self.state.selected_items(&self.items)
+    }
+
+    /// Enables or disables the item with the given index.
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(item) = self.items.get_mut(index) {
+            item.enabled = enabled;
+        }
+    }
+
+    /// Selects or deselects the item with the given index in a multi-select menu.
+    pub fn set_selected(&mut self, index: usize, selected: bool) {
+        if let Some(item) = self.items.get_mut(index) {
+            item.selected = selected;
+        }
+    }
+
+    /// Sets whether more than one item can be selected. Turning it off deselects all items.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, Input, Menu, MenuEvent, MenuItem};
+    ///
+    /// let window = initscr();
+    /// let mut menu = Menu::new(vec![MenuItem::new("One", ""), MenuItem::new("Two", "")]);
+    /// menu.set_multi_select(true);
+    /// menu.handle_input(&window, &Input::Character(' '));
+    /// menu.handle_input(&window, &Input::KeyDown);
+    /// assert_eq!(
+    ///     Some(MenuEvent::Toggled(1, true)),
+    ///     menu.handle_input(&window, &Input::Character(' '))
+    /// );
+    /// assert_eq!(vec![0, 1], menu.selected_items());
+    /// endwin();
+    /// ```
+    pub fn set_multi_select(&mut self, multi_select: bool) {
+        self.state.set_multi_select(&mut self.items, multi_select);
+    }
+
+    /// Sets whether the descriptions of the items are shown next to their names. They're shown by
+    /// default.
+    pub fn set_show_descriptions(&mut self, show_descriptions: bool) {
+        self.show_descriptions = show_descriptions;
+    }
+
+    /// Sets the attributes of the current item, `A_REVERSE` by default.
+    pub fn set_highlight(&mut self, attributes: chtype) {
+        self.highlight = attributes;
+    }
+
+    /// Sets the attributes of disabled items, `A_DIM` by default.
+    pub fn set_disabled_attributes(&mut self, attributes: chtype) {
+        self.disabled = attributes;
+    }
+
+    /// The characters typed so far to jump to an item.
+    pub fn pattern(&self) -> &str {
+        self.state.search()
+    }
+
+    /// Draws the menu into the window, starting from its upper left corner.
+    ///
+    /// If there are more items than lines in the window, the menu is scrolled so that the current
+    /// item is visible.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, Menu, MenuItem, A_CHARTEXT};
+    ///
+    /// let window = initscr();
+    /// let items = (0..10).map(|i| MenuItem::new(format!("Item {}", i), "")).collect();
+    /// let mut menu = Menu::new(items);
+    /// let subwindow = window.derwin(3, 20, 0, 0).unwrap();
+    /// menu.set_current(9);
+    /// menu.draw(&subwindow).unwrap();
+    /// assert_eq!('7', (subwindow.mvinch(0, 5) & A_CHARTEXT) as u8 as char);
+    /// endwin();
+    /// ```
+    pub fn draw(&mut self, window: &Window) -> Result<()> {
+        let (rows, cols) = window.get_max_yx();
+        self.state
+            .scroll_to_current(self.items.len(), rows as usize);
+
+        let mark_width = if self.state.multi_select() { 4 } else { 0 };
+        let name_width = self
+            .items
+            .iter()
+            .map(|item| display_width(&item.name))
+            .max()
+            .unwrap_or(0);
+
+        for row in 0..rows {
+            window.mv(row, 0)?;
+            window.clrtoeol()?;
+            let index = self.state.top() + row as usize;
+            let item = match self.items.get(index) {
+                Some(item) => item,
+                None => continue,
+            };
+
+            let mut line = self.state.check_box(item).to_string();
+            line.push_str(&item.name);
+            if self.show_descriptions && !item.description.is_empty() {
+                let padding = mark_width + name_width + 2 - display_width(&line);
+                line.push_str(&" ".repeat(padding));
+                line.push_str(&item.description);
+            }
+
+            let mut attributes = 0;
+            if self.state.current(self.items.len()) == Some(index) {
+                attributes |= self.highlight;
+            }
+            if !item.enabled {
+                attributes |= self.disabled;
+            }
+            draw_line(window, line, attributes, cols, row == rows - 1)?;
+        }
+        Ok(())
+    }
+
+    /// Moves through, chooses or toggles the items according to input read from the window the
+    /// menu is drawn in. Returns None for input that leaves the menu as it was.
+    ///
+    /// `Input::Mouse` is handled by handle_mouse().
+    pub fn handle_input(&mut self, window: &Window, input: &Input) -> Option<MenuEvent> {
+        match *input {
+            Input::Mouse(ref event) => self.handle_mouse(window, event),
+            _ => {
+                let page = window.get_max_y().max(1) as usize;
+                self.state
+                    .handle_key(&mut self.items, input, page, |name, pattern| {
+                        name.starts_with(pattern)
+                    })
+                    .map(MenuEvent::from_change)
+            }
+        }
+    }
+
+    /// Handles a mouse event in the window the menu is drawn in.
+    ///
+    /// A click on an item chooses it, or toggles it in a multi-select menu, and the scroll wheel
+    /// moves the current item. Events outside of the window are ignored.
+    pub fn handle_mouse(&mut self, window: &Window, event: &MouseEvent) -> Option<MenuEvent> {
+        let (y, x) = event.position;
+        if !window.enclose(y, x) {
+            return None;
+        }
+        let change = match (event.kind, event.button) {
+            (MouseEventKind::WheelUp, _) => self.state.move_by(&self.items, -1),
+            (MouseEventKind::WheelDown, _) => self.state.move_by(&self.items, 1),
+            (MouseEventKind::Clicked, Some(MouseButton::Left))
+            | (MouseEventKind::Pressed, Some(MouseButton::Left)) => {
+                let (row, _) = window.mouse_trafo(y, x, false);
+                let index = self.state.top() + row as usize;
+                match self.items.get(index) {
+                    Some(item) if item.enabled => {
+                        self.state.set_current(self.items.len(), index);
+                        if self.state.multi_select() {
+                            self.state.toggle(&mut self.items)
+                        } else {
+                            self.state.select(&self.items)
+                        }
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        change.map(MenuEvent::from_change)
+    }
+}
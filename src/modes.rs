@@ -0,0 +1,201 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::check;
+use crate::{platform_specific, Result};
+
+/// A terminal mode that curses doesn't know about, switched with escape sequences.
+struct Mode {
+    function: &'static str,
+    enabled: AtomicBool,
+    enable: &'static str,
+    disable: &'static str,
+    /// Whether the terminal reports the mouse in a way getch() decodes instead of curses.
+    mouse: bool,
+}
+
+impl Mode {
+    fn set(&self, enabled: bool) -> Result<()> {
+        let sequence = if enabled { self.enable } else { self.disable };
+        check(self.function, platform_specific::_write_terminal(sequence))?;
+        if self.mouse {
+            check(
+                self.function,
+                platform_specific::_set_mouse_decoding(!enabled),
+            )?;
+        }
+        self.enabled.store(enabled, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+static BRACKETED_PASTE: Mode = Mode {
+    function: "set_bracketed_paste",
+    enabled: AtomicBool::new(false),
+    enable: "\x1b[?2004h",
+    disable: "\x1b[?2004l",
+    mouse: false,
+};
+
+static FOCUS_REPORTING: Mode = Mode {
+    function: "set_focus_reporting",
+    enabled: AtomicBool::new(false),
+    enable: "\x1b[?1004h",
+    disable: "\x1b[?1004l",
+    mouse: false,
+};
+
+static BUTTON_MOTION_TRACKING: Mode = Mode {
+    function: "set_mouse_tracking",
+    enabled: AtomicBool::new(false),
+    enable: "\x1b[?1002h\x1b[?1006h",
+    disable: "\x1b[?1002l\x1b[?1006l",
+    mouse: true,
+};
+
+static ANY_MOTION_TRACKING: Mode = Mode {
+    function: "set_mouse_tracking",
+    enabled: AtomicBool::new(false),
+    enable: "\x1b[?1003h\x1b[?1006h",
+    disable: "\x1b[?1003l\x1b[?1006l",
+    mouse: true,
+};
+
+static MODES: [&Mode; 4] = [
+    &BRACKETED_PASTE,
+    &FOCUS_REPORTING,
+    &BUTTON_MOTION_TRACKING,
+    &ANY_MOTION_TRACKING,
+];
+
+/// Whether any of the modes is enabled, so that getch() has control sequences to decode.
+pub(crate) fn reports_sequences() -> bool {
+    MODES.iter().any(|mode| mode.enabled.load(Ordering::SeqCst))
+}
+
+/// Which mouse events the terminal reports, see `set_mouse_tracking()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MouseTracking {
+    /// Leave the mouse to curses and mousemask().
+    Off,
+    /// Report presses, releases, the wheel and movement while a button is held down.
+    ButtonMotion,
+    /// Like `ButtonMotion`, but also report movement when no button is held down.
+    AnyMotion,
+}
+
+/// Enables or disables bracketed paste mode.
+///
+/// When it's enabled the terminal marks text that is pasted into it, and getch() returns the text
+/// as a single `Input::Paste` instead of one character at a time. This way newlines in the text
+/// don't trigger actions. Terminals that don't support bracketed paste ignore it. endwin()
+/// disables the mode again, so enable it again after resuming from a temporary escape.
+///
+/// (Not supported on Windows, where pasted text comes in as key presses and this returns an error)
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, set_bracketed_paste, Input};
+///
+/// let window = initscr();
+/// if set_bracketed_paste(true).is_ok() {
+///     // This is what the terminal sends when "hello\nworld" is pasted
+///     for c in "\x1b[200~hello\nworld\x1b[201~".chars().rev() {
+///         window.ungetch(&Input::Character(c)).unwrap();
+///     }
+///     assert_eq!(Some(Input::Paste("hello\nworld".to_string())), window.getch());
+/// }
+/// endwin();
+/// ```
+pub fn set_bracketed_paste(enabled: bool) -> Result<()> {
+    BRACKETED_PASTE.set(enabled)
+}
+
+/// Enables or disables focus reporting.
+///
+/// When it's enabled getch() returns `Input::FocusGained` and `Input::FocusLost` as the terminal
+/// window gains and loses the focus, e.g. to pause animations while the program isn't being looked
+/// at. Terminals that don't support focus reporting ignore it. endwin() disables the mode again.
+///
+/// (Not supported on Windows, where this returns an error)
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, set_focus_reporting, Input};
+///
+/// let window = initscr();
+/// if set_focus_reporting(true).is_ok() {
+///     window.ungetch(&Input::FocusLost).unwrap();
+///     assert_eq!(Some(Input::FocusLost), window.getch());
+/// }
+/// endwin();
+/// ```
+pub fn set_focus_reporting(enabled: bool) -> Result<()> {
+    FOCUS_REPORTING.set(enabled)
+}
+
+/// Switches the terminal's own mouse tracking on or off.
+///
+/// Curses reports mouse movement unreliably, loses track of which button is held down while
+/// dragging, and on many terminals can't report positions past column 223. With tracking enabled
+/// the terminal reports every mouse event with SGR (1006) encoding, which getch() decodes into
+/// `Input::Mouse` on its own: `MouseEventKind::Dragged` carries the button that is held down, and
+/// positions are correct on screens of any size. The events aren't filtered by mousemask(), and
+/// clicks are reported as separate presses and releases. Terminals that don't support the modes
+/// ignore them. Switching tracking off hands the mouse back to curses, and endwin() switches it
+/// off as well.
+///
+/// (Not supported on Windows, where PDCurses reports the mouse on its own and switching tracking on
+/// returns an error)
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, set_mouse_tracking, Input, Modifiers, MouseButton};
+/// use pancurses::{MouseEvent, MouseEventKind, MouseTracking};
+///
+/// let window = initscr();
+/// window.keypad(true).unwrap();
+/// if set_mouse_tracking(MouseTracking::ButtonMotion).is_ok() {
+///     // This is what the terminal sends when the mouse is dragged to column 300 with the left
+///     // button held down
+///     for c in "\x1b[<32;301;5M".chars().rev() {
+///         window.ungetch(&Input::Character(c)).unwrap();
+///     }
+///     let drag = MouseEvent {
+///         kind: MouseEventKind::Dragged,
+///         button: Some(MouseButton::Left),
+///         modifiers: Modifiers::NONE,
+///         position: (4, 300),
+///     };
+///     assert_eq!(Some(Input::Mouse(drag)), window.getch());
+///     set_mouse_tracking(MouseTracking::Off).unwrap();
+/// }
+/// endwin();
+/// ```
+pub fn set_mouse_tracking(tracking: MouseTracking) -> Result<()> {
+    let modes = [
+        (MouseTracking::ButtonMotion, &BUTTON_MOTION_TRACKING),
+        (MouseTracking::AnyMotion, &ANY_MOTION_TRACKING),
+    ];
+    for &(_, mode) in modes.iter().filter(|&&(t, _)| t != tracking) {
+        if mode.enabled.load(Ordering::SeqCst) {
+            mode.set(false)?;
+        }
+    }
+    for &(_, mode) in modes.iter().filter(|&&(t, _)| t == tracking) {
+        mode.set(true)?;
+    }
+    Ok(())
+}
+
+/// Returns whether getch() decodes the mouse instead of curses.
+pub(crate) fn mouse_tracking() -> bool {
+    MODES
+        .iter()
+        .any(|mode| mode.mouse && mode.enabled.load(Ordering::SeqCst))
+}
+
+/// Disables the modes that are enabled, before curses restores the terminal.
+pub(crate) fn reset() {
+    for mode in MODES.iter() {
+        if mode.enabled.load(Ordering::SeqCst) {
+            let _ = mode.set(false);
+        }
+    }
+}
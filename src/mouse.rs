@@ -0,0 +1,259 @@
+use crate::{mmask_t, Modifiers, MEVENT};
+use crate::{BUTTON1_CLICKED, BUTTON1_DOUBLE_CLICKED, BUTTON1_PRESSED, BUTTON1_RELEASED};
+use crate::{BUTTON1_TRIPLE_CLICKED, BUTTON2_CLICKED, BUTTON2_DOUBLE_CLICKED, BUTTON2_PRESSED};
+use crate::{BUTTON2_RELEASED, BUTTON2_TRIPLE_CLICKED, BUTTON3_CLICKED, BUTTON3_DOUBLE_CLICKED};
+use crate::{BUTTON3_PRESSED, BUTTON3_RELEASED, BUTTON3_TRIPLE_CLICKED, BUTTON4_PRESSED};
+use crate::{BUTTON5_PRESSED, BUTTON_ALT, BUTTON_CTRL, BUTTON_SHIFT, REPORT_MOUSE_POSITION};
+
+/// What happened in a `MouseEvent`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MouseEventKind {
+    Pressed,
+    Released,
+    Clicked,
+    DoubleClicked,
+    TripleClicked,
+    WheelUp,
+    WheelDown,
+    Moved,
+    /// The mouse moved while a button was held down, only reported with `set_mouse_tracking()`.
+    Dragged,
+}
+
+/// A mouse button.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+/// The kinds of events that are reported for each button, in the order of `BUTTON_KINDS`.
+const BUTTON_MASKS: [(MouseButton, [mmask_t; 5]); 3] = [
+    (
+        MouseButton::Left,
+        [
+            BUTTON1_PRESSED,
+            BUTTON1_RELEASED,
+            BUTTON1_CLICKED,
+            BUTTON1_DOUBLE_CLICKED,
+            BUTTON1_TRIPLE_CLICKED,
+        ],
+    ),
+    (
+        MouseButton::Middle,
+        [
+            BUTTON2_PRESSED,
+            BUTTON2_RELEASED,
+            BUTTON2_CLICKED,
+            BUTTON2_DOUBLE_CLICKED,
+            BUTTON2_TRIPLE_CLICKED,
+        ],
+    ),
+    (
+        MouseButton::Right,
+        [
+            BUTTON3_PRESSED,
+            BUTTON3_RELEASED,
+            BUTTON3_CLICKED,
+            BUTTON3_DOUBLE_CLICKED,
+            BUTTON3_TRIPLE_CLICKED,
+        ],
+    ),
+];
+
+const BUTTON_KINDS: [MouseEventKind; 5] = [
+    MouseEventKind::Pressed,
+    MouseEventKind::Released,
+    MouseEventKind::Clicked,
+    MouseEventKind::DoubleClicked,
+    MouseEventKind::TripleClicked,
+];
+
+const MODIFIER_MASKS: [(Modifiers, mmask_t); 3] = [
+    (Modifiers::SHIFT, BUTTON_SHIFT),
+    (Modifiers::CTRL, BUTTON_CTRL),
+    (Modifiers::ALT, BUTTON_ALT),
+];
+
+/// The bits of the button number in SGR mouse reports.
+const SGR_BUTTONS: [MouseButton; 3] = [MouseButton::Left, MouseButton::Middle, MouseButton::Right];
+const SGR_NO_BUTTON: u32 = 3;
+const SGR_MODIFIERS: [(Modifiers, u32); 3] = [
+    (Modifiers::SHIFT, 4),
+    (Modifiers::ALT, 8),
+    (Modifiers::CTRL, 16),
+];
+const SGR_MOTION: u32 = 32;
+const SGR_WHEEL: u32 = 64;
+
+/// A mouse event, returned by getch() as `Input::Mouse` when mouse events are enabled with
+/// mousemask().
+///
+/// This is the same on all platforms, unlike the `MEVENT` returned by getmouse() and its
+/// `bstate`, whose bits differ between ncurses and PDCurses.
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, mousemask, Input, Modifiers, MouseButton, MouseEvent};
+/// use pancurses::{MouseEventKind, ALL_MOUSE_EVENTS};
+///
+/// let window = initscr();
+/// window.keypad(true).unwrap();
+/// mousemask(ALL_MOUSE_EVENTS, None);
+/// let click = MouseEvent {
+///     kind: MouseEventKind::Clicked,
+///     button: Some(MouseButton::Left),
+///     modifiers: Modifiers::CTRL,
+///     position: (2, 10),
+/// };
+/// window.ungetch(&Input::Mouse(click)).unwrap();
+/// assert_eq!(Some(Input::Mouse(click)), window.getch());
+/// endwin();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    /// The button the event is about, None for the wheel and for moving the mouse.
+    pub button: Option<MouseButton>,
+    /// The modifier keys that were held down. Terminals can only report Shift, Ctrl and Alt, and
+    /// often use some of them for their own purposes.
+    pub modifiers: Modifiers,
+    /// The position of the mouse on the screen as (y, x), see `Window::mouse_trafo()` for turning
+    /// it into a position in a window.
+    pub position: (i32, i32),
+}
+
+impl MouseEvent {
+    /// Converts an event returned by getmouse().
+    ///
+    /// Events that are neither for the wheel nor for one of the three buttons are reported as
+    /// `MouseEventKind::Moved`.
+    pub fn from_mevent(event: &MEVENT) -> MouseEvent {
+        let bstate = event.bstate;
+        let mut modifiers = Modifiers::NONE;
+        for &(modifier, mask) in MODIFIER_MASKS.iter() {
+            if bstate & mask != 0 {
+                modifiers |= modifier;
+            }
+        }
+        let (kind, button) = if bstate & BUTTON4_PRESSED != 0 {
+            (MouseEventKind::WheelUp, None)
+        } else if bstate & BUTTON5_PRESSED != 0 {
+            (MouseEventKind::WheelDown, None)
+        } else {
+            BUTTON_MASKS
+                .iter()
+                .flat_map(|&(button, ref masks)| {
+                    masks
+                        .iter()
+                        .zip(BUTTON_KINDS.iter())
+                        .map(move |(&mask, &kind)| (mask, kind, button))
+                })
+                .find(|&(mask, _, _)| bstate & mask != 0)
+                .map_or((MouseEventKind::Moved, None), |(_, kind, button)| {
+                    (kind, Some(button))
+                })
+        };
+        MouseEvent {
+            kind,
+            button,
+            modifiers,
+            position: (event.y, event.x),
+        }
+    }
+
+    /// Converts the event into the form getmouse() returns, for ungetmouse().
+    pub(crate) fn to_mevent(self) -> MEVENT {
+        let mut bstate = match (self.kind, self.button) {
+            (MouseEventKind::WheelUp, _) => BUTTON4_PRESSED,
+            (MouseEventKind::WheelDown, _) => BUTTON5_PRESSED,
+            (MouseEventKind::Moved, _) | (MouseEventKind::Dragged, _) | (_, None) => {
+                REPORT_MOUSE_POSITION
+            }
+            (kind, Some(button)) => {
+                let &(_, masks) = BUTTON_MASKS.iter().find(|&&(b, _)| b == button).unwrap();
+                let index = BUTTON_KINDS.iter().position(|&k| k == kind).unwrap();
+                masks[index]
+            }
+        };
+        for &(modifier, mask) in MODIFIER_MASKS.iter() {
+            if self.modifiers.contains(modifier) {
+                bstate |= mask;
+            }
+        }
+        MEVENT {
+            id: 0,
+            x: self.position.1,
+            y: self.position.0,
+            z: 0,
+            bstate,
+        }
+    }
+
+    /// Decodes the parameters of an SGR mouse report, "<button;x;y" followed by 'M' for presses
+    /// and motion or 'm' for releases.
+    pub(crate) fn from_sgr(sequence: &str) -> Option<MouseEvent> {
+        let released = sequence.ends_with('m');
+        if !sequence.starts_with('<') || !(released || sequence.ends_with('M')) {
+            return None;
+        }
+        let mut parameters = sequence[1..sequence.len() - 1].split(';');
+        let mut next = || parameters.next().and_then(|p| p.parse::<u32>().ok());
+        let (code, x, y) = (next()?, next()?, next()?);
+        if next().is_some() || x == 0 || y == 0 {
+            return None;
+        }
+        let mut modifiers = Modifiers::NONE;
+        for &(modifier, bit) in SGR_MODIFIERS.iter() {
+            if code & bit != 0 {
+                modifiers |= modifier;
+            }
+        }
+        let number = code & SGR_NO_BUTTON;
+        let button = SGR_BUTTONS.get(number as usize).cloned();
+        let (kind, button) = if code & SGR_WHEEL != 0 {
+            match number {
+                0 => (MouseEventKind::WheelUp, None),
+                1 => (MouseEventKind::WheelDown, None),
+                _ => (MouseEventKind::Moved, None), // Horizontal scrolling
+            }
+        } else if code & SGR_MOTION != 0 {
+            match button {
+                Some(_) => (MouseEventKind::Dragged, button),
+                None => (MouseEventKind::Moved, None),
+            }
+        } else if released {
+            (MouseEventKind::Released, button)
+        } else {
+            (MouseEventKind::Pressed, button)
+        };
+        Some(MouseEvent {
+            kind,
+            button,
+            modifiers,
+            position: (y as i32 - 1, x as i32 - 1),
+        })
+    }
+
+    /// Encodes the event the way the terminal reports it with `set_mouse_tracking()`, for the
+    /// kinds of events that only come in that way.
+    pub(crate) fn to_sgr(self) -> Option<String> {
+        let mut code = match (self.kind, self.button) {
+            (MouseEventKind::Dragged, Some(button)) => {
+                SGR_MOTION + SGR_BUTTONS.iter().position(|&b| b == button).unwrap() as u32
+            }
+            _ => return None,
+        };
+        for &(modifier, bit) in SGR_MODIFIERS.iter() {
+            if self.modifiers.contains(modifier) {
+                code |= bit;
+            }
+        }
+        Some(format!(
+            "\x1b[<{};{};{}M",
+            code,
+            self.position.1 + 1,
+            self.position.0 + 1
+        ))
+    }
+}
@@ -0,0 +1,327 @@
+use std::cmp;
+use std::ops::Deref;
+
+use crate::error::{check, check_ptr};
+#[cfg(any(windows, feature = "wide"))]
+use crate::ComplexChar;
+use crate::{chtype, curses, platform_specific, window, ControlChars, Result, ToChtype, Window};
+
+/// Defines methods of a pad that do the same as the methods of `Window` with the same names.
+macro_rules! window_methods {
+    ($($(#[$attribute:meta])* fn $name:ident[$($generics:tt)*]($($argument:ident: $type:ty),*)
+        -> $result:ty;)*) => {
+        $(
+            $(#[$attribute])*
+            #[doc = concat!("See `Window::", stringify!($name), "()`.")]
+            pub fn $name<$($generics)*>(&self, $($argument: $type),*) -> $result {
+                self.window.$name($($argument),*)
+            }
+        )*
+    };
+}
+
+/// A pad, a window that isn't tied to the screen and can be larger than it.
+///
+/// A pad is created with `newpad()`. It has the drawing methods of a `Window`, but instead of
+/// being refreshed in place a rectangle of the pad is copied to a rectangle of the screen. Moving
+/// the rectangle of the pad is how a pad is scrolled. The methods of a window that only make sense
+/// for a window on the screen, such as getch() and subwin(), aren't available for pads:
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, newpad};
+///
+/// let window = initscr();
+/// let pad = newpad(100, 40).unwrap();
+/// for line in 0..100 {
+///     pad.mvaddstr(line, 0, format!("Line {}", line)).unwrap();
+/// }
+/// window.refresh().unwrap();
+///
+/// // Show lines 50 to 59 of the pad in the top left corner of the screen
+/// pad.refresh(50, 0, 0, 0, 9, 39).unwrap();
+/// // Scrolling past the end of the pad shows the last ten lines
+/// pad.refresh(95, 0, 0, 0, 9, 39).unwrap();
+/// endwin();
+/// ```
+///
+/// ```compile_fail
+/// use pancurses::{endwin, initscr, newpad};
+///
+/// let window = initscr();
+/// let pad = newpad(100, 40).unwrap();
+/// // Input is read from windows on the screen
+/// pad.getch();
+/// endwin();
+/// ```
+#[derive(Debug)]
+pub struct Pad {
+    window: Window,
+}
+
+pub fn new_pad(window: Window) -> Pad {
+    Pad { window }
+}
+
+impl Pad {
+    /// Deletes the pad, freeing all associated memory.
+    pub fn delwin(self) -> Result<()> {
+        self.window.delwin()
+    }
+
+    window_methods! {
+        fn addch[T: ToChtype](ch: T) -> Result<()>;
+        #[cfg(any(windows, feature = "wide"))]
+        fn add_wch[](wch: &ComplexChar) -> Result<()>;
+        fn addstr[T: AsRef<str>](string: T) -> Result<()>;
+        fn addnstr[T: AsRef<str>](string: T, length: usize) -> Result<()>;
+        fn addstr_cols[T: AsRef<str>](string: T, max_cols: usize, ellipsis: Option<&str>)
+            -> Result<usize>;
+        fn attrget[]() -> (chtype, i16);
+        fn attroff[T: Into<chtype>](attributes: T) -> Result<()>;
+        fn attron[T: Into<chtype>](attributes: T) -> Result<()>;
+        fn attrset[T: Into<chtype>](attributes: T) -> Result<()>;
+        fn bkgd[T: Into<chtype>](ch: T) -> Result<()>;
+        fn bkgdset[T: Into<chtype>](ch: T) -> ();
+        #[allow(clippy::too_many_arguments)]
+        fn border[T: ToChtype](
+            left_side: T,
+            right_side: T,
+            top_side: T,
+            bottom_side: T,
+            top_left_corner: T,
+            top_right_corner: T,
+            bottom_left_corner: T,
+            bottom_right_corner: T
+        ) -> Result<()>;
+        fn chgat[](n: i32, attributes: chtype, color_pair: i16) -> Result<()>;
+        fn clear[]() -> Result<()>;
+        fn clrtobot[]() -> Result<()>;
+        fn clrtoeol[]() -> Result<()>;
+        fn color_set[](color_pair: i16) -> Result<()>;
+        fn control_chars[]() -> ControlChars;
+        fn delch[]() -> Result<()>;
+        fn deleteln[]() -> Result<()>;
+        fn draw_box[T: ToChtype](verch: T, horch: T) -> Result<()>;
+        fn erase[]() -> Result<()>;
+        fn getbkgd[]() -> chtype;
+        fn get_cur_x[]() -> i32;
+        fn get_cur_y[]() -> i32;
+        fn get_cur_yx[]() -> (i32, i32);
+        fn get_max_x[]() -> i32;
+        fn get_max_y[]() -> i32;
+        fn get_max_yx[]() -> (i32, i32);
+        fn hline[T: ToChtype](ch: T, n: i32) -> Result<()>;
+        fn insdelln[](n: i32) -> Result<()>;
+        fn insertln[]() -> Result<()>;
+        fn insch[T: ToChtype](ch: T) -> Result<()>;
+        #[cfg(any(windows, feature = "wide"))]
+        fn in_wch[]() -> Result<ComplexChar>;
+        #[cfg(any(windows, feature = "wide"))]
+        fn ins_wch[](wch: &ComplexChar) -> Result<()>;
+        fn is_linetouched[](line: i32) -> bool;
+        fn is_touched[]() -> bool;
+        fn mv[](y: i32, x: i32) -> Result<()>;
+        fn mvaddch[T: ToChtype](y: i32, x: i32, ch: T) -> Result<()>;
+        #[cfg(any(windows, feature = "wide"))]
+        fn mvadd_wch[](y: i32, x: i32, wch: &ComplexChar) -> Result<()>;
+        fn mvaddstr[T: AsRef<str>](y: i32, x: i32, string: T) -> Result<()>;
+        fn mvaddnstr[T: AsRef<str>](y: i32, x: i32, string: T, n: i32) -> Result<()>;
+        fn mvaddstr_cols[T: AsRef<str>](
+            y: i32,
+            x: i32,
+            string: T,
+            max_cols: usize,
+            ellipsis: Option<&str>
+        ) -> Result<usize>;
+        fn mvchgat[](y: i32, x: i32, n: i32, attributes: chtype, color_pair: i16) -> Result<()>;
+        fn mvinch[](y: i32, x: i32) -> chtype;
+        #[cfg(any(windows, feature = "wide"))]
+        fn mvin_wch[](y: i32, x: i32) -> Result<ComplexChar>;
+        #[cfg(any(windows, feature = "wide"))]
+        fn mvins_wch[](y: i32, x: i32, wch: &ComplexChar) -> Result<()>;
+        fn mvinsch[T: ToChtype](y: i32, x: i32, ch: T) -> Result<()>;
+        fn mvprintw[T: AsRef<str>](y: i32, x: i32, string: T) -> Result<()>;
+        fn printw[T: AsRef<str>](string: T) -> Result<()>;
+        fn resize[](nlines: i32, ncols: i32) -> Result<()>;
+        fn scrollok[](bf: bool) -> Result<()>;
+        fn set_control_chars[](control_chars: ControlChars) -> ();
+        fn setscrreg[](top: i32, bot: i32) -> Result<()>;
+        fn touch[]() -> Result<()>;
+        fn touchline[](start: i32, count: i32) -> Result<()>;
+        fn touchln[](y: i32, n: i32, changed: bool) -> Result<()>;
+        fn untouch[]() -> Result<()>;
+        fn vline[T: ToChtype](ch: T, n: i32) -> Result<()>;
+    }
+
+    /// The window curses keeps for the pad, for drawing into it with code written for windows.
+    pub(crate) fn window(&self) -> &Window {
+        &self.window
+    }
+
+    /// Copies a rectangle of the pad to the virtual screen, without updating the physical screen.
+    ///
+    /// This is the pad version of `Window::noutrefresh()`, see `refresh()` for the parameters.
+    /// Call `doupdate()` to update the physical screen.
+    pub fn noutrefresh(
+        &self,
+        pminrow: i32,
+        pmincol: i32,
+        sminrow: i32,
+        smincol: i32,
+        smaxrow: i32,
+        smaxcol: i32,
+    ) -> Result<()> {
+        match self.viewport(pminrow, pmincol, sminrow, smincol, smaxrow, smaxcol) {
+            Some((pminrow, pmincol, sminrow, smincol, smaxrow, smaxcol)) => {
+                check("pnoutrefresh", unsafe {
+                    curses::pnoutrefresh(
+                        self.window.as_ptr(),
+                        pminrow,
+                        pmincol,
+                        sminrow,
+                        smincol,
+                        smaxrow,
+                        smaxcol,
+                    )
+                })
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Copies a rectangle of the pad to the screen and updates the physical screen.
+    ///
+    /// The rectangle of the pad starts at (pminrow, pmincol), and it's shown in the rectangle of
+    /// the screen from (sminrow, smincol) to (smaxrow, smaxcol), both corners inclusive.
+    ///
+    /// Unlike the C function, the rectangles are clamped instead of causing an error: the screen
+    /// rectangle is cut to fit on the screen and to be no larger than the pad, and the pad
+    /// position is kept within the pad so that scrolling past either end shows the first or last
+    /// full page of the pad. Nothing is done if the screen rectangle is entirely off screen.
+    pub fn refresh(
+        &self,
+        pminrow: i32,
+        pmincol: i32,
+        sminrow: i32,
+        smincol: i32,
+        smaxrow: i32,
+        smaxcol: i32,
+    ) -> Result<()> {
+        match self.viewport(pminrow, pmincol, sminrow, smincol, smaxrow, smaxcol) {
+            Some((pminrow, pmincol, sminrow, smincol, smaxrow, smaxcol)) => {
+                check("prefresh", unsafe {
+                    curses::prefresh(
+                        self.window.as_ptr(),
+                        pminrow,
+                        pmincol,
+                        sminrow,
+                        smincol,
+                        smaxrow,
+                        smaxcol,
+                    )
+                })
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Creates a subpad within the pad.
+    ///
+    /// The subpad is nlines lines by ncols columns, and it's positioned at (begy, begx) within
+    /// this pad. Like a subwindow it shares its characters with this pad, which it borrows so
+    /// that this pad can't be dropped before it.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, newpad, A_CHARTEXT};
+    ///
+    /// let window = initscr();
+    /// let pad = newpad(50, 50).unwrap();
+    /// let subpad = pad.subpad(10, 10, 20, 20).unwrap();
+    /// subpad.mvaddstr(0, 0, "Inside").unwrap();
+    /// assert_eq!('I', (pad.mvinch(20, 20) & A_CHARTEXT) as u8 as char);
+    /// subpad.refresh(0, 0, 0, 0, 9, 9).unwrap();
+    /// endwin();
+    /// ```
+    pub fn subpad(&self, nlines: i32, ncols: i32, begy: i32, begx: i32) -> Result<SubPad<'_>> {
+        let window_pointer = check_ptr("subpad", unsafe {
+            curses::subpad(self.window.as_ptr(), nlines, ncols, begy, begx)
+        })?;
+        let window = window::new_window(window_pointer, false);
+        window.set_control_chars(self.window.control_chars());
+        Ok(SubPad {
+            pad: Pad { window },
+            parent: self,
+        })
+    }
+
+    /// Clamps the rectangles given to refresh() to the screen and the pad.
+    #[allow(clippy::type_complexity)]
+    fn viewport(
+        &self,
+        pminrow: i32,
+        pmincol: i32,
+        sminrow: i32,
+        smincol: i32,
+        smaxrow: i32,
+        smaxcol: i32,
+    ) -> Option<(i32, i32, i32, i32, i32, i32)> {
+        let screen = platform_specific::_stdscr();
+        let (screen_rows, screen_cols) =
+            unsafe { (curses::getmaxy(screen), curses::getmaxx(screen)) };
+        let (pad_rows, pad_cols) = self.window.get_max_yx();
+
+        let sminrow = cmp::max(sminrow, 0);
+        let smincol = cmp::max(smincol, 0);
+        let smaxrow = cmp::min(cmp::min(smaxrow, screen_rows - 1), sminrow + pad_rows - 1);
+        let smaxcol = cmp::min(cmp::min(smaxcol, screen_cols - 1), smincol + pad_cols - 1);
+        if smaxrow < sminrow || smaxcol < smincol {
+            return None;
+        }
+
+        let pminrow = cmp::max(cmp::min(pminrow, pad_rows - (smaxrow - sminrow + 1)), 0);
+        let pmincol = cmp::max(cmp::min(pmincol, pad_cols - (smaxcol - smincol + 1)), 0);
+        Some((pminrow, pmincol, sminrow, smincol, smaxrow, smaxcol))
+    }
+}
+
+/// A pad created within another pad with subpad().
+///
+/// A subpad shares the characters of its parent, so it borrows the parent to make sure it isn't
+/// dropped first. It dereferences to a `Pad` and is refreshed the same way, but only gives out
+/// shared references to it, so the pad can't be taken out of the subpad to outlive the parent:
+///
+/// ```compile_fail
+/// use pancurses::{endwin, initscr, newpad};
+/// use std::mem;
+///
+/// let window = initscr();
+/// let parent = newpad(50, 50).unwrap();
+/// let mut subpad = parent.subpad(10, 10, 0, 0).unwrap();
+/// let escaped = mem::replace(&mut *subpad, newpad(1, 1).unwrap());
+/// endwin();
+/// ```
+#[derive(Debug)]
+pub struct SubPad<'parent> {
+    pad: Pad,
+    parent: &'parent Pad,
+}
+
+impl<'parent> SubPad<'parent> {
+    /// Deletes the subpad, freeing all associated memory.
+    pub fn delwin(self) -> Result<()> {
+        self.pad.delwin()
+    }
+
+    /// The pad this subpad was created in.
+    pub fn parent(&self) -> &'parent Pad {
+        self.parent
+    }
+}
+
+impl<'parent> Deref for SubPad<'parent> {
+    type Target = Pad;
+
+    fn deref(&self) -> &Pad {
+        &self.pad
+    }
+}
@@ -0,0 +1,169 @@
+use std::cell::RefCell;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::check;
+use crate::window::WindowPointer;
+use crate::{curses, platform_specific, Result, Window};
+
+static NEXT_PANEL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A panel in the stack, from the bottom to the top.
+struct Entry {
+    id: usize,
+    window: WindowPointer,
+    hidden: bool,
+}
+
+thread_local! {
+    static STACK: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A window that is part of a stack of overlapping windows.
+///
+/// Panels are drawn on top of the standard screen in the order of the stack, so that the window of
+/// the topmost panel is fully visible and the windows below it are obscured where they overlap.
+/// Instead of refreshing the windows of the panels one by one, call `update_panels()` followed by
+/// `doupdate()` to repaint the whole stack in the right order.
+///
+/// This is a pure Rust implementation of the curses panel library, so it works the same way on all
+/// platforms.
+///
+/// ```rust
+/// use pancurses::{doupdate, endwin, initscr, new_panel, newwin, update_panels};
+///
+/// let window = initscr();
+/// let back = new_panel(newwin(10, 20, 2, 2).unwrap());
+/// let front = new_panel(newwin(10, 20, 5, 10).unwrap());
+/// back.draw_box(0, 0).unwrap();
+/// front.draw_box(0, 0).unwrap();
+///
+/// // Bring the back panel to the front
+/// back.top();
+/// front.move_panel(6, 12).unwrap();
+/// update_panels().unwrap();
+/// doupdate().unwrap();
+///
+/// front.hide();
+/// assert!(front.is_hidden());
+/// update_panels().unwrap();
+/// doupdate().unwrap();
+/// endwin();
+/// ```
+#[derive(Debug)]
+pub struct Panel {
+    id: usize,
+    window: Window,
+}
+
+/// Puts the window in a new panel at the top of the stack.
+pub fn new_panel(window: Window) -> Panel {
+    let id = NEXT_PANEL_ID.fetch_add(1, Ordering::Relaxed);
+    STACK.with(|stack| {
+        stack.borrow_mut().push(Entry {
+            id,
+            window: window.as_ptr(),
+            hidden: false,
+        })
+    });
+    Panel { id, window }
+}
+
+/// Refreshes the virtual screen to show the panels in the order of the stack.
+///
+/// The standard screen is refreshed first, followed by the visible panels from the bottom to the
+/// top. This does not update the physical screen, call `doupdate()` for that.
+pub fn update_panels() -> Result<()> {
+    let stdscr = platform_specific::_stdscr();
+    check("touchwin", unsafe { curses::touchwin(stdscr) })?;
+    check("wnoutrefresh", unsafe { curses::wnoutrefresh(stdscr) })?;
+    STACK.with(|stack| {
+        for entry in stack.borrow().iter().filter(|entry| !entry.hidden) {
+            check("touchwin", unsafe { curses::touchwin(entry.window) })?;
+            check("wnoutrefresh", unsafe {
+                curses::wnoutrefresh(entry.window)
+            })?;
+        }
+        Ok(())
+    })
+}
+
+impl Panel {
+    /// Puts the panel at the bottom of the stack.
+    pub fn bottom(&self) {
+        self.with_entry(|stack, index| {
+            let entry = stack.remove(index);
+            stack.insert(0, entry);
+        })
+    }
+
+    /// Removes the panel from view, without taking it out of the stack.
+    pub fn hide(&self) {
+        self.with_entry(|stack, index| stack[index].hidden = true)
+    }
+
+    /// Returns true if the panel is hidden.
+    pub fn is_hidden(&self) -> bool {
+        self.with_entry(|stack, index| stack[index].hidden)
+    }
+
+    /// Moves the window of the panel so that its upper left-hand corner is at (y, x) on the screen.
+    ///
+    /// Use this instead of mvwin() on the window of the panel.
+    pub fn move_panel(&self, y: i32, x: i32) -> Result<()> {
+        self.window.mvwin(y, x)
+    }
+
+    /// Makes a hidden panel visible again and puts it at the top of the stack.
+    pub fn show(&self) {
+        self.with_entry(|stack, index| {
+            let mut entry = stack.remove(index);
+            entry.hidden = false;
+            stack.push(entry);
+        })
+    }
+
+    /// Puts the panel at the top of the stack.
+    pub fn top(&self) {
+        self.with_entry(|stack, index| {
+            let entry = stack.remove(index);
+            stack.push(entry);
+        })
+    }
+
+    /// The window of the panel.
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    fn with_entry<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce(&mut Vec<Entry>, usize) -> T,
+    {
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let index = stack
+                .iter()
+                .position(|entry| entry.id == self.id)
+                .expect("Panel is missing from the stack");
+            f(&mut stack, index)
+        })
+    }
+}
+
+impl Deref for Panel {
+    type Target = Window;
+
+    fn deref(&self) -> &Window {
+        &self.window
+    }
+}
+
+/// Take the panel out of the stack when it's dropped
+impl Drop for Panel {
+    fn drop(&mut self) {
+        let id = self.id;
+        // The stack may already be gone if the panel is dropped while the thread is exiting
+        let _ = STACK.try_with(|stack| stack.borrow_mut().retain(|entry| entry.id != id));
+    }
+}
@@ -0,0 +1,113 @@
+use std::rc::{Rc, Weak};
+
+use crate::{doupdate, platform_specific, resize_term, window, Result, Window};
+
+/// Places windows for a screen of the given size, or returns None once they are gone.
+pub(crate) type Placement<'a> = Box<dyn FnMut(i32, i32) -> Option<Result<()>> + 'a>;
+
+/// Keeps windows laid out on the screen as the terminal is resized.
+///
+/// Each window is registered with a layout callback, which is given the number of lines and
+/// columns of the screen and returns the size and position of the window as (nlines, ncols, begy,
+/// begx), like the arguments to newwin(). When getch() returns `Input::KeyResize`, resize() has
+/// curses adjust to the new size of the terminal, resizes and moves the windows to where their
+/// callbacks put them, and redraws the screen. This works the same on ncurses and PDCurses, which
+/// doesn't resize subwindows along with their parents by itself.
+///
+/// The manager only keeps weak references to the windows, and forgets about windows once they are
+/// dropped. An `EventLoop` calls resize() by itself, see `EventLoop::resize_manager()`.
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, newwin, Input, ResizeManager};
+/// use std::rc::Rc;
+///
+/// let window = initscr();
+/// let mut manager = ResizeManager::new();
+/// let status_bar = Rc::new(newwin(1, 1, 0, 0).unwrap());
+/// // Keep the status bar on the last line, across the whole screen
+/// manager.register(&status_bar, |lines, cols| (1, cols, lines - 1, 0));
+/// manager.resize().unwrap();
+/// assert_eq!((window.get_max_y() - 1, 0), status_bar.get_beg_yx());
+/// assert_eq!((1, window.get_max_x()), status_bar.get_max_yx());
+///
+/// // The loop of the program
+/// if let Some(Input::KeyResize) = window.getch() {
+///     manager.resize().unwrap();
+/// }
+/// endwin();
+/// ```
+#[derive(Default)]
+pub struct ResizeManager<'a> {
+    windows: Vec<Placement<'a>>,
+}
+
+impl<'a> ResizeManager<'a> {
+    /// Creates a manager without any windows.
+    pub fn new() -> ResizeManager<'a> {
+        ResizeManager::default()
+    }
+
+    /// Registers a window, which is laid out with the callback each time the terminal is resized.
+    ///
+    /// The windows are laid out in the order they were registered in, so register windows before
+    /// their subwindows.
+    pub fn register<W, F>(&mut self, window: &Rc<W>, mut layout: F)
+    where
+        W: AsRef<Window> + 'a,
+        F: FnMut(i32, i32) -> (i32, i32, i32, i32) + 'a,
+    {
+        let window: Weak<W> = Rc::downgrade(window);
+        self.add(Box::new(move |lines, cols| {
+            window
+                .upgrade()
+                .map(|window| place((*window).as_ref(), layout(lines, cols)))
+        }));
+    }
+
+    pub(crate) fn add(&mut self, placement: Placement<'a>) {
+        self.windows.push(placement);
+    }
+
+    /// Adjusts curses to the new size of the terminal, lays out the registered windows and
+    /// redraws the screen. Call this when getch() returns `Input::KeyResize`.
+    ///
+    /// All the windows are laid out even if some of them can't be, in which case the first error
+    /// is returned.
+    pub fn resize(&mut self) -> Result<()> {
+        // PDCurses needs this to adjust to the new size, ncurses has done it already
+        let _ = resize_term(0, 0);
+        let stdscr = window::new_window(platform_specific::_stdscr(), true);
+        let (lines, cols) = stdscr.get_max_yx();
+        // The windows go on top of the standard screen
+        stdscr.touch()?;
+        stdscr.noutrefresh()?;
+
+        let mut result = Ok(());
+        self.windows
+            .retain_mut(|placement| match placement(lines, cols) {
+                Some(placed) => {
+                    if result.is_ok() {
+                        result = placed;
+                    }
+                    true
+                }
+                None => false,
+            });
+        doupdate()?;
+        result
+    }
+}
+
+/// Resizes and moves the window to the given (nlines, ncols, begy, begx).
+pub(crate) fn place(
+    window: &Window,
+    (nlines, ncols, begy, begx): (i32, i32, i32, i32),
+) -> Result<()> {
+    let (lines, cols) = window.get_max_yx();
+    // Shrink the window first so that it fits on the screen at both the old and the new position
+    window.resize(nlines.min(lines), ncols.min(cols))?;
+    window.mvwin(begy, begx)?;
+    window.resize(nlines, ncols)?;
+    window.touch()?;
+    window.noutrefresh()
+}
@@ -0,0 +1,152 @@
+use std::ops::Deref;
+use std::panic;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+use crate::{curses, modes, platform_specific, window, Error, Result, Window};
+
+/// Set while a `Session` is alive.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Set from a call to initscr() outside of a session until the next call to endwin(), while a
+/// session would alias the standard screen of initscr().
+static INITSCR: AtomicBool = AtomicBool::new(false);
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+/// Returns true if a `Session` is currently alive.
+pub(crate) fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Records that curses was initialized with initscr() outside of a session, or left with endwin().
+pub(crate) fn set_initscr(initialized: bool) {
+    INITSCR.store(initialized, Ordering::SeqCst);
+}
+
+/// An initialized curses session that owns the standard screen.
+///
+/// The session dereferences to the `Window` of the standard screen. When it is dropped `endwin()`
+/// is called, restoring the terminal to the state it was in before the session was started. The
+/// terminal is also restored if the program panics while the session is alive, before the panic
+/// message is printed, so that the message and any backtrace are readable. The terminal opened by
+/// `try_initscr()` is deleted once the session and the windows created while it was alive have all
+/// been dropped.
+///
+/// Only one session can be alive at a time.
+///
+/// ```rust
+/// use pancurses::{Error, Session};
+///
+/// fn main() -> pancurses::Result<()> {
+///     let session = Session::new()?;
+///     session.printw("Hello Rust")?;
+///     session.refresh()?;
+///     assert_eq!(Err(Error::AlreadyInitialized), Session::new().map(|_| ()));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Session {
+    stdscr: Window,
+    screen: Option<Rc<platform_specific::Screen>>,
+}
+
+impl Session {
+    /// Initialize the curses system with initscr() and start a new session.
+    ///
+    /// Returns `Error::AlreadyInitialized` if another session is already alive, or if curses was
+    /// initialized with initscr() and endwin() hasn't been called since. Like initscr(), on ncurses this prints an error and
+    /// exits the process if the terminal can't be initialized, see `try_initscr()` for an
+    /// alternative that returns an error instead.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, Error, Session};
+    ///
+    /// let window = initscr();
+    /// assert_eq!(Err(Error::AlreadyInitialized), Session::new().map(|_| ()));
+    /// endwin();
+    /// // Once curses has been left a session can take over
+    /// let session = Session::new().unwrap();
+    /// ```
+    pub fn new() -> Result<Session> {
+        Session::start(|| {
+            platform_specific::pre_init();
+            unsafe { curses::initscr() };
+            Ok(None)
+        })
+    }
+
+    /// Start a new session on a terminal opened with newterm(), see `try_initscr()`.
+    pub(crate) fn with_newterm() -> Result<Session> {
+        Session::start(|| platform_specific::_try_newterm().map(Some))
+    }
+
+    fn start<F>(init: F) -> Result<Session>
+    where
+        F: FnOnce() -> Result<Option<platform_specific::Screen>>,
+    {
+        if INITSCR.load(Ordering::SeqCst) || ACTIVE.swap(true, Ordering::SeqCst) {
+            return Err(Error::AlreadyInitialized);
+        }
+        install_panic_hook();
+        match init() {
+            Ok(screen) => {
+                let screen = screen.map(Rc::new);
+                window::set_screen(screen.clone());
+                Ok(Session {
+                    stdscr: window::new_window(platform_specific::_stdscr(), true),
+                    screen,
+                })
+            }
+            Err(error) => {
+                ACTIVE.store(false, Ordering::SeqCst);
+                Err(error)
+            }
+        }
+    }
+
+    /// The window of the standard screen.
+    pub fn stdscr(&self) -> &Window {
+        &self.stdscr
+    }
+}
+
+impl Deref for Session {
+    type Target = Window;
+
+    fn deref(&self) -> &Window {
+        &self.stdscr
+    }
+}
+
+/// Restore the terminal when the session ends
+impl Drop for Session {
+    fn drop(&mut self) {
+        modes::reset();
+        unsafe {
+            curses::endwin();
+        }
+        #[cfg(unix)]
+        platform_specific::_use_standard_streams();
+        // The screen is deleted once the windows created on it are gone as well
+        window::set_screen(None);
+        self.screen.take();
+        ACTIVE.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Chain a panic hook that leaves curses mode before the panic message is printed.
+fn install_panic_hook() {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if is_active() {
+                modes::reset();
+                unsafe {
+                    curses::endwin();
+                }
+            }
+            previous_hook(info);
+        }));
+    });
+}
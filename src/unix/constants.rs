@@ -82,6 +82,7 @@ pub const A_UNDERLINE: attr_t = ncurses::A_UNDERLINE();
 
 pub const KEY_OFFSET: i32 = 0o0400;
 pub const KEY_RESIZE: i32 = ncurses::KEY_RESIZE;
+pub const KEY_F0: i32 = ncurses::KEY_F0;
 pub const KEY_F15: i32 = ncurses::KEY_F15;
 pub const KEY_EVENT: i32 = ncurses::KEY_EVENT;
 
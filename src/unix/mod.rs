@@ -2,21 +2,160 @@
 pub mod constants;
 use self::constants::*;
 
-use ncurses::{box_, getmouse, keyname, COLORS, COLOR_PAIRS};
+#[cfg(feature = "wide")]
+mod wide;
+#[cfg(feature = "wide")]
+pub use self::wide::*;
+
+use ncurses::{box_, getmouse, keyname, stdscr, COLORS, COLOR_PAIRS, TABSIZE};
 use ncurses::ll::{chtype, ungetch, wattroff, wattron, wattrset, MEVENT, NCURSES_ATTR_T, WINDOW};
-use ncurses::ll::{resize_term, wgetch};
+use ncurses::ll::{delscreen, newterm, resize_term, tigetstr, wgetch, FILE_p, SCREEN};
 
-use libc::{c_int, setlocale, LC_ALL};
-use crate::input::Input;
+use libc::{c_char, c_int, c_void, setlocale, LC_ALL, STDIN_FILENO, STDOUT_FILENO};
+use crate::input::{Input, Key, Modifiers};
+use crate::{Error, ERR};
 
-use std::ffi::CString;
+use std::env;
+use std::ffi::{CStr, CString};
+use std::io;
+use std::ptr;
+use std::sync::atomic::{AtomicI32, Ordering};
 use std::string::FromUtf8Error;
 
+extern "C" {
+    fn setupterm(term: *const c_char, fildes: c_int, errret: *mut c_int) -> c_int;
+    fn set_curterm(nterm: *mut c_void) -> *mut c_void;
+    fn del_curterm(oterm: *mut c_void) -> c_int;
+    fn key_defined(definition: *const c_char) -> c_int;
+    fn keyok(keycode: c_int, enable: bool) -> c_int;
+}
+
 pub fn pre_init() {
     let buf = CString::new("").unwrap();
     unsafe { setlocale(LC_ALL, buf.as_ptr()) };
 }
 
+/// The file descriptors the standard screen reads its input from and writes its output to.
+static INPUT_FD: AtomicI32 = AtomicI32::new(STDIN_FILENO);
+static OUTPUT_FD: AtomicI32 = AtomicI32::new(STDOUT_FILENO);
+
+/// A terminal opened with newterm(), along with the streams it reads from and writes to.
+#[derive(Debug)]
+pub struct Screen {
+    screen: SCREEN,
+    output: FILE_p,
+    input: FILE_p,
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        unsafe {
+            delscreen(self.screen);
+            libc::fclose(self.output);
+            libc::fclose(self.input);
+        }
+    }
+}
+
+/// Opens a stream on a duplicate of the given file descriptor, so that closing the stream
+/// leaves the original open.
+fn dup_stream(fd: c_int, mode: &str) -> FILE_p {
+    let mode = CString::new(mode).unwrap();
+    unsafe {
+        let duplicate = libc::dup(fd);
+        if duplicate < 0 {
+            return ptr::null_mut();
+        }
+        let stream = libc::fdopen(duplicate, mode.as_ptr());
+        if stream.is_null() {
+            libc::close(duplicate);
+        }
+        stream
+    }
+}
+
+/// Initializes curses with newterm() on the standard input and output, reporting the problems
+/// that would make initscr() exit the process as errors instead.
+pub fn _try_newterm() -> crate::Result<Screen> {
+    if unsafe { libc::isatty(STDIN_FILENO) == 0 || libc::isatty(STDOUT_FILENO) == 0 } {
+        return Err(Error::NotATty);
+    }
+
+    let name = env::var("TERM").unwrap_or_default();
+    if name.is_empty() {
+        return Err(Error::UnknownTerminal { name });
+    }
+    let term = CString::new(name.as_str()).map_err(|_| Error::UnknownTerminal {
+        name: name.clone(),
+    })?;
+
+    // Look the terminal up first, since newterm() only tells us that it failed and not why. This
+    // replaces the current terminal, which is put back afterwards.
+    let previous = unsafe { set_curterm(ptr::null_mut()) };
+    let mut errret: c_int = 0;
+    let found = unsafe { setupterm(term.as_ptr(), STDOUT_FILENO, &mut errret) } != ERR;
+    unsafe { del_curterm(set_curterm(previous)) };
+    if !found {
+        return Err(match errret {
+            -1 => Error::MissingTerminfo { name },
+            0 => Error::UnknownTerminal { name },
+            _ => Error::Curses {
+                function: "setupterm",
+            },
+        });
+    }
+
+    pre_init();
+    let output = dup_stream(STDOUT_FILENO, "w");
+    let input = dup_stream(STDIN_FILENO, "r");
+    let screen = if output.is_null() || input.is_null() {
+        ptr::null_mut()
+    } else {
+        unsafe { newterm(term.as_ptr(), output, input) }
+    };
+    if screen.is_null() {
+        unsafe {
+            if !output.is_null() {
+                libc::fclose(output);
+            }
+            if !input.is_null() {
+                libc::fclose(input);
+            }
+        }
+        return Err(Error::NullPointer {
+            function: "newterm",
+        });
+    }
+    _use_streams(output, input);
+    Ok(Screen {
+        screen,
+        output,
+        input,
+    })
+}
+
+/// Records the streams of a terminal opened with newterm() as the ones the standard screen uses.
+pub fn _use_streams(output: FILE_p, input: FILE_p) {
+    unsafe {
+        INPUT_FD.store(libc::fileno(input), Ordering::SeqCst);
+        OUTPUT_FD.store(libc::fileno(output), Ordering::SeqCst);
+    }
+}
+
+/// Goes back to the standard input and output as the streams of the standard screen.
+pub fn _use_standard_streams() {
+    INPUT_FD.store(STDIN_FILENO, Ordering::SeqCst);
+    OUTPUT_FD.store(STDOUT_FILENO, Ordering::SeqCst);
+}
+
+pub fn _input_fd() -> c_int {
+    INPUT_FD.load(Ordering::SeqCst)
+}
+
+pub fn _stdscr() -> WINDOW {
+    stdscr()
+}
+
 pub fn _attron(w: WINDOW, attributes: chtype) -> i32 {
     unsafe { wattron(w, attributes as NCURSES_ATTR_T) }
 }
@@ -33,6 +172,10 @@ pub fn _COLORS() -> i32 {
     COLORS()
 }
 
+pub fn _TABSIZE() -> i32 {
+    TABSIZE()
+}
+
 pub fn _COLOR_PAIRS() -> i32 {
     COLOR_PAIRS()
 }
@@ -57,6 +200,24 @@ pub fn _getmouse() -> Result<MEVENT, i32> {
     }
 }
 
+pub fn _ungetmouse(event: &MEVENT) -> i32 {
+    let mut event = *event;
+    unsafe { ncurses::ll::ungetmouse(&mut event) }
+}
+
+/// Sets whether ncurses decodes the mouse sequences the terminal sends, or passes them on.
+pub fn _set_mouse_decoding(enabled: bool) -> i32 {
+    if enabled {
+        // Have ncurses set up the terminal for its own mouse support again
+        let mut mask = 0;
+        ncurses::mousemask(0, Some(&mut mask));
+        ncurses::mousemask(mask, None);
+    }
+    // This fails before keypad mode was first enabled, then there is nothing to do yet
+    unsafe { keyok(ncurses::KEY_MOUSE, enabled) };
+    0
+}
+
 pub fn _keyname(code: i32) -> Option<String> {
     keyname(code)
 }
@@ -65,6 +226,44 @@ pub fn _resize_term(nlines: i32, ncols: i32) -> i32 {
     unsafe { resize_term(nlines, ncols) }
 }
 
+pub fn _get_escdelay() -> i32 {
+    ncurses::ESCDELAY()
+}
+
+pub fn _set_escdelay(milliseconds: i32) -> i32 {
+    ncurses::set_escdelay(milliseconds)
+}
+
+/// How long to wait for the input that follows an escape character. With keypad() enabled
+/// ncurses has already waited for the escape delay before returning the escape character, so
+/// anything that follows it is already there.
+pub fn _escape_wait(w: WINDOW) -> i32 {
+    if ncurses::is_keypad(w) {
+        0
+    } else {
+        ncurses::ESCDELAY()
+    }
+}
+
+/// Writes an escape sequence that curses doesn't know about to the terminal of the standard
+/// screen. It goes straight to the file descriptor curses writes to, so the mode takes effect
+/// right away instead of with the next refresh.
+pub fn _write_terminal(sequence: &str) -> i32 {
+    let fd = OUTPUT_FD.load(Ordering::SeqCst);
+    let mut bytes = sequence.as_bytes();
+    while !bytes.is_empty() {
+        let written = unsafe { libc::write(fd, bytes.as_ptr() as *const c_void, bytes.len()) };
+        if written < 0 {
+            if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return ERR;
+        }
+        bytes = &bytes[written as usize..];
+    }
+    0
+}
+
 pub fn _set_blink(_: bool) -> i32 {
     0 // Not supported
 }
@@ -75,15 +274,138 @@ pub fn _set_title(_: &str) {
 
 /// Converts an integer returned by getch() to a Input value
 pub fn to_special_keycode(i: i32) -> Option<Input> {
+    // The function keys above F12 are the ones with modifiers on terminals that send them with an
+    // xterm modifier parameter. The others above F15 have no place in SPECIAL_KEY_CODES.
+    let function_key = i - KEY_F0;
+    if (13..=MAX_FUNCTION_KEY).contains(&function_key) {
+        if let Some(input) = modified_function_key(function_key) {
+            return Some(input);
+        }
+        if function_key > 15 {
+            let key = Key::F(function_key as u8);
+            return Some(Input::KeyModified(key, Modifiers::NONE));
+        }
+    }
     let index = if i <= KEY_F15 {
         i - KEY_OFFSET
     } else {
         i - KEY_OFFSET - 48
     };
     if index < 0 || index as usize >= SPECIAL_KEY_CODES.len() {
+        modified_key(i)
+    } else {
+        Some(SPECIAL_KEY_CODES[index as usize].clone())
+    }
+}
+
+/// The terminfo names of the keys that can be combined with modifiers. Extended key names add the
+/// xterm modifier parameter to these, so kUP5 is Ctrl+Up and kLFT3 is Alt+Left.
+const MODIFIABLE_KEYS: [(&str, Key); 10] = [
+    ("kUP", Key::Up),
+    ("kDN", Key::Down),
+    ("kLFT", Key::Left),
+    ("kRIT", Key::Right),
+    ("kHOM", Key::Home),
+    ("kEND", Key::End),
+    ("kPRV", Key::PageUp),
+    ("kNXT", Key::PageDown),
+    ("kIC", Key::Insert),
+    ("kDC", Key::Delete),
+];
+
+/// The highest function key ncurses has a key code for.
+const MAX_FUNCTION_KEY: i32 = 63;
+
+/// Looks up a string capability of the current terminal.
+fn terminfo_string(name: &str) -> Option<Vec<u8>> {
+    let name = CString::new(name).unwrap();
+    let value = unsafe { tigetstr(name.as_ptr()) };
+    // Absent capabilities are null and ones that aren't strings are (char *) -1
+    if value.is_null() || value as isize == -1 {
         None
     } else {
-        Some(SPECIAL_KEY_CODES[index as usize])
+        Some(unsafe { CStr::from_ptr(value) }.to_bytes().to_vec())
+    }
+}
+
+/// Converts a key code that ncurses assigned to an extended key name to an Input value.
+fn modified_key(i: i32) -> Option<Input> {
+    if i < KEY_OFFSET {
+        return None;
+    }
+    let name = keyname(i)?;
+    // Some terminfo entries describe the focus reports as keys
+    match name.as_str() {
+        "kxIN" => return Some(Input::FocusGained),
+        "kxOUT" => return Some(Input::FocusLost),
+        _ => {}
+    }
+    MODIFIABLE_KEYS.iter().find_map(|&(prefix, key)| {
+        let parameter = name.strip_prefix(prefix)?.parse::<u32>().ok()?;
+        Some(Input::KeyModified(
+            key,
+            Modifiers::from_parameter(parameter),
+        ))
+    })
+}
+
+/// Decodes the function key with the given number as a key from F1 to F12 with modifiers, or
+/// returns None if its escape sequence doesn't carry any.
+fn modified_function_key(n: i32) -> Option<Input> {
+    let sequence = terminfo_string(&format!("kf{}", n))?;
+    decode_function_key(&sequence, |base| terminfo_string(&format!("kf{}", base)))
+}
+
+/// Terminals send function keys with modifiers as the escape sequence of the plain key with an
+/// xterm modifier parameter added, e.g. "\E[15;5~" for Ctrl+F5 where F5 is "\E[15~". terminfo
+/// lists these as the function keys above F12, so the sequence tells which key was pressed.
+///
+/// `plain_key` looks up the escape sequences of F1 to F12. Returns None for a sequence without a
+/// modifier parameter or one that isn't based on any of them.
+fn decode_function_key<F>(sequence: &[u8], plain_key: F) -> Option<Input>
+where
+    F: Fn(u8) -> Option<Vec<u8>>,
+{
+    let separator = sequence.iter().rposition(|&b| b == b';')?;
+    let digits = sequence[separator + 1..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    let end = separator + 1 + digits;
+    let parameter = std::str::from_utf8(&sequence[separator + 1..end])
+        .ok()?
+        .parse::<u32>()
+        .ok()?;
+    let mut plain = sequence[..separator].to_vec();
+    plain.extend_from_slice(&sequence[end..]);
+    // F1 to F4 are sent as "\EOP" to "\EOS" without modifiers, but as "\E[1;2P" with them
+    let alternative = match plain.as_slice() {
+        [0x1b, b'[', b'1', last] => Some(vec![0x1b, b'O', *last]),
+        _ => None,
+    };
+    (1..=12)
+        .find(|&base| {
+            plain_key(base).is_some_and(|s| s == plain || Some(&s) == alternative.as_ref())
+        })
+        .map(|base| Input::KeyModified(Key::F(base), Modifiers::from_parameter(parameter)))
+}
+
+/// Finds the key code ncurses uses for a key with modifiers.
+fn modified_key_code(key: Key, modifiers: Modifiers) -> Option<i32> {
+    let input = Input::KeyModified(key, modifiers);
+    if let Key::F(_) = key {
+        return (13..=MAX_FUNCTION_KEY)
+            .map(|n| KEY_F0 + n)
+            .find(|&code| to_special_keycode(code).as_ref() == Some(&input));
+    }
+    let &(prefix, _) = MODIFIABLE_KEYS.iter().find(|&&(_, k)| k == key)?;
+    let sequence = terminfo_string(&format!("{}{}", prefix, modifiers.parameter()))?;
+    let sequence = CString::new(sequence).ok()?;
+    let code = unsafe { key_defined(sequence.as_ptr()) };
+    if code > 0 {
+        Some(code)
+    } else {
+        None
     }
 }
 
@@ -100,9 +422,14 @@ pub fn _ungetch(input: &Input) -> i32 {
                 .fold(0, |res, x| res.min(x))
         }
         Input::Unknown(i) => unsafe { ungetch(i) },
-        specialKeyCode => {
+        Input::KeyEscape => unsafe { ungetch(0x1b) },
+        Input::KeyModified(key, modifiers) => match modified_key_code(key, modifiers) {
+            Some(code) => unsafe { ungetch(code) },
+            None => ERR,
+        },
+        ref specialKeyCode => {
             for (i, skc) in SPECIAL_KEY_CODES.iter().enumerate() {
-                if *skc == specialKeyCode {
+                if skc == specialKeyCode {
                     let result = i as c_int + KEY_OFFSET;
                     if result <= KEY_F15 {
                         return unsafe { ungetch(result) };
@@ -120,6 +447,8 @@ pub fn _wgetch(w: WINDOW) -> Option<Input> {
     let i = unsafe { wgetch(w) };
     if i < 0 {
         None
+    } else if i > 0xff {
+        Some(to_special_keycode(i).unwrap_or(Input::Unknown(i)))
     } else {
         Some(to_special_keycode(i).unwrap_or_else(|| {
             // Assume that on Linux input is UTF-8
@@ -148,7 +477,7 @@ pub fn _wgetch(w: WINDOW) -> Option<Input> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::input::Input;
+    use crate::input::{Input, Key, Modifiers};
     use ncurses::{endwin, initscr};
 
     #[test]
@@ -169,6 +498,42 @@ mod tests {
         assert_eq!(Input::KeyUp, to_special_keycode(keyUp).unwrap());
     }
 
+    /// The escape sequences xterm's terminfo entry has for F1 to F12.
+    fn xterm_function_key(n: u8) -> Option<Vec<u8>> {
+        let sequences = [
+            "\x1bOP", "\x1bOQ", "\x1bOR", "\x1bOS", "\x1b[15~", "\x1b[17~", "\x1b[18~", "\x1b[19~",
+            "\x1b[20~", "\x1b[21~", "\x1b[23~", "\x1b[24~",
+        ];
+        sequences.get(n as usize - 1).map(|s| s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_modified_function_key_to_input() {
+        // xterm sends Shift+F1 as F13, Ctrl+F5 as F29 and Alt+F1 as F49
+        assert_eq!(
+            Some(Input::KeyModified(Key::F(1), Modifiers::SHIFT)),
+            decode_function_key(b"\x1b[1;2P", xterm_function_key)
+        );
+        assert_eq!(
+            Some(Input::KeyModified(Key::F(5), Modifiers::CTRL)),
+            decode_function_key(b"\x1b[15;5~", xterm_function_key)
+        );
+        assert_eq!(
+            Some(Input::KeyModified(Key::F(1), Modifiers::ALT)),
+            decode_function_key(b"\x1b[1;3P", xterm_function_key)
+        );
+        assert_eq!(
+            Some(Input::KeyModified(
+                Key::F(12),
+                Modifiers::CTRL | Modifiers::SHIFT
+            )),
+            decode_function_key(b"\x1b[24;6~", xterm_function_key)
+        );
+        // Function keys of their own, and ones not based on F1 to F12
+        assert_eq!(None, decode_function_key(b"\x1b[25~", xterm_function_key));
+        assert_eq!(None, decode_function_key(b"\x1b[25;2~", xterm_function_key));
+    }
+
     #[test]
     fn test_ungetch() {
         let w = initscr();
@@ -186,7 +551,18 @@ mod tests {
 
         SPECIAL_KEY_CODES.iter().for_each(|i| {
             _ungetch(i);
-            assert_eq!(_wgetch(w).unwrap(), *i);
+            let input = _wgetch(w).unwrap();
+            match *i {
+                // Terminals that send Shift+F1 to Shift+F3 as F13 to F15 report them that way
+                Input::KeyF13 | Input::KeyF14 | Input::KeyF15 => assert!(
+                    input == *i
+                        || matches!(input, Input::KeyModified(Key::F(1..=3), Modifiers::SHIFT)),
+                    "{:?} came back as {:?}",
+                    i,
+                    input
+                ),
+                _ => assert_eq!(input, *i),
+            }
         });
 
         endwin();
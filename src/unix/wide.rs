@@ -0,0 +1,165 @@
+use libc::{c_int, c_short, c_void, wchar_t};
+use ncurses::ll::{attr_t, wget_wch, winttype, WINDOW};
+use ncurses::{ERR, KEY_CODE_YES};
+use std::char;
+use std::ptr;
+
+use super::constants::A_COLOR;
+use super::to_special_keycode;
+use crate::complexchar::CCHARW_MAX;
+use crate::{ComplexChar, Input};
+
+/// The cchar_t of ncursesw. Its fields are only accessed through setcchar() and getcchar().
+#[repr(C)]
+struct cchar_t {
+    attr: attr_t,
+    chars: [wchar_t; CCHARW_MAX],
+    ext_color: c_int,
+}
+
+extern "C" {
+    fn setcchar(
+        wcval: *mut cchar_t,
+        wch: *const wchar_t,
+        attrs: attr_t,
+        color_pair: c_short,
+        opts: *mut c_void,
+    ) -> c_int;
+    fn getcchar(
+        wcval: *const cchar_t,
+        wch: *mut wchar_t,
+        attrs: *mut attr_t,
+        color_pair: *mut c_short,
+        opts: *mut c_void,
+    ) -> c_int;
+    fn wadd_wch(w: WINDOW, wch: *const cchar_t) -> c_int;
+    fn mvwadd_wch(w: WINDOW, y: c_int, x: c_int, wch: *const cchar_t) -> c_int;
+    fn win_wch(w: WINDOW, wcval: *mut cchar_t) -> c_int;
+    fn mvwin_wch(w: WINDOW, y: c_int, x: c_int, wcval: *mut cchar_t) -> c_int;
+    fn wins_wch(w: WINDOW, wch: *const cchar_t) -> c_int;
+    fn mvwins_wch(w: WINDOW, y: c_int, x: c_int, wch: *const cchar_t) -> c_int;
+}
+
+fn empty_cchar() -> cchar_t {
+    cchar_t {
+        attr: 0,
+        chars: [0; CCHARW_MAX],
+        ext_color: 0,
+    }
+}
+
+/// Calls f with the cchar_t built from wch, or returns ERR if setcchar() rejects it.
+fn with_cchar<F: FnOnce(*const cchar_t) -> c_int>(wch: &ComplexChar, f: F) -> i32 {
+    let mut text: Vec<wchar_t> = wch.text().chars().map(|c| c as wchar_t).collect();
+    text.push(0);
+    let mut cchar = empty_cchar();
+    let result = unsafe {
+        setcchar(
+            &mut cchar,
+            text.as_ptr(),
+            wch.attributes(),
+            wch.color_pair(),
+            ptr::null_mut(),
+        )
+    };
+    if result == ERR {
+        ERR
+    } else {
+        f(&cchar)
+    }
+}
+
+/// Reads a cchar_t with f and converts it into a ComplexChar.
+fn read_cchar<F: FnOnce(*mut cchar_t) -> c_int>(f: F) -> Result<ComplexChar, i32> {
+    let mut cchar = empty_cchar();
+    if f(&mut cchar) == ERR {
+        return Err(ERR);
+    }
+    let mut text: [wchar_t; CCHARW_MAX + 1] = [0; CCHARW_MAX + 1];
+    let mut attributes: attr_t = 0;
+    let mut color_pair: c_short = 0;
+    let result = unsafe {
+        getcchar(
+            &cchar,
+            text.as_mut_ptr(),
+            &mut attributes,
+            &mut color_pair,
+            ptr::null_mut(),
+        )
+    };
+    if result == ERR {
+        return Err(ERR);
+    }
+    let text: String = text
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| char::from_u32(c as u32).unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+    ComplexChar::new(text, attributes & !A_COLOR, color_pair).map_err(|_| ERR)
+}
+
+pub fn _wadd_wch(w: WINDOW, wch: &ComplexChar) -> i32 {
+    with_cchar(wch, |cchar| unsafe { wadd_wch(w, cchar) })
+}
+
+pub fn _mvwadd_wch(w: WINDOW, y: i32, x: i32, wch: &ComplexChar) -> i32 {
+    with_cchar(wch, |cchar| unsafe { mvwadd_wch(w, y, x, cchar) })
+}
+
+pub fn _win_wch(w: WINDOW) -> Result<ComplexChar, i32> {
+    read_cchar(|cchar| unsafe { win_wch(w, cchar) })
+}
+
+pub fn _mvwin_wch(w: WINDOW, y: i32, x: i32) -> Result<ComplexChar, i32> {
+    read_cchar(|cchar| unsafe { mvwin_wch(w, y, x, cchar) })
+}
+
+pub fn _wins_wch(w: WINDOW, wch: &ComplexChar) -> i32 {
+    with_cchar(wch, |cchar| unsafe { wins_wch(w, cchar) })
+}
+
+pub fn _mvwins_wch(w: WINDOW, y: i32, x: i32, wch: &ComplexChar) -> i32 {
+    with_cchar(wch, |cchar| unsafe { mvwins_wch(w, y, x, cchar) })
+}
+
+pub fn _wget_wch(w: WINDOW) -> Option<Input> {
+    let mut wch: winttype = 0;
+    match unsafe { wget_wch(w, &mut wch) } {
+        ERR => None,
+        KEY_CODE_YES => Some(to_special_keycode(wch as i32).unwrap_or(Input::Unknown(wch as i32))),
+        _ => Some(
+            char::from_u32(wch)
+                .map(Input::Character)
+                .unwrap_or(Input::Unknown(wch as i32)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::A_BOLD;
+    use libc::{setlocale, LC_ALL};
+    use std::ffi::CString;
+
+    #[test]
+    fn test_cchar_round_trip() {
+        // Combining characters are only recognized in a UTF-8 locale
+        let locale = CString::new("C.UTF-8").unwrap();
+        unsafe { setlocale(LC_ALL, locale.as_ptr()) };
+
+        let wch = ComplexChar::new("e\u{301}", A_BOLD, 3).unwrap();
+        let mut copy = empty_cchar();
+        with_cchar(&wch, |cchar| {
+            unsafe { ptr::copy(cchar, &mut copy, 1) };
+            0
+        });
+        assert_eq!(
+            Ok(wch),
+            read_cchar(|cchar| {
+                unsafe { ptr::copy(&copy, cchar, 1) };
+                0
+            })
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::io;
+use std::os::unix::io::RawFd;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::{input_fd, Error, Result};
+
+/// The ends of the pipe that wakes up getch(), -1 until the first `Waker` is created.
+static READ_FD: AtomicI32 = AtomicI32::new(-1);
+static WRITE_FD: AtomicI32 = AtomicI32::new(-1);
+static CREATE: Mutex<()> = Mutex::new(());
+
+/// Whether curses may hold input that the terminal no longer reports as readable.
+static PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Wakes up a getch() that is waiting for input in another thread, which then returns
+/// `Input::Wakeup`.
+///
+/// This lets the thread that runs the interface sleep in getch() until there is either input or
+/// something else to show, such as the result of a background job. Any number of wakers can be
+/// created and sent to other threads. Wakeups that happen while nothing waits for input are
+/// returned by the next getch(), and several of them at once are returned as a single
+/// `Input::Wakeup`.
+///
+/// (Only supported on Unix)
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, Input, Waker};
+/// use std::thread;
+///
+/// let window = initscr();
+/// let waker = Waker::new().unwrap();
+/// thread::spawn(move || {
+///     // ... do some work in the background ...
+///     waker.wake().unwrap();
+/// })
+/// .join()
+/// .unwrap();
+/// assert_eq!(Some(Input::Wakeup), window.getch());
+/// endwin();
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Waker {
+    fd: RawFd,
+}
+
+impl Waker {
+    /// Creates a waker, setting up the pipe that wakes getch() the first time.
+    pub fn new() -> Result<Waker> {
+        let _lock = CREATE.lock().unwrap_or_else(|e| e.into_inner());
+        if WRITE_FD.load(Ordering::SeqCst) < 0 {
+            let mut fds = [0; 2];
+            if unsafe { libc::pipe(fds.as_mut_ptr()) } < 0 {
+                return Err(last_error("pipe"));
+            }
+            for &fd in fds.iter() {
+                unsafe {
+                    libc::fcntl(fd, libc::F_SETFL, libc::O_NONBLOCK);
+                    libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+                }
+            }
+            READ_FD.store(fds[0], Ordering::SeqCst);
+            WRITE_FD.store(fds[1], Ordering::SeqCst);
+        }
+        Ok(Waker {
+            fd: WRITE_FD.load(Ordering::SeqCst),
+        })
+    }
+
+    /// Makes getch() return `Input::Wakeup`.
+    pub fn wake(&self) -> Result<()> {
+        let byte = 0u8;
+        let written = unsafe { libc::write(self.fd, &byte as *const u8 as *const libc::c_void, 1) };
+        // A full pipe already has a wakeup waiting
+        if written < 0 && io::Error::last_os_error().kind() != io::ErrorKind::WouldBlock {
+            return Err(last_error("wake"));
+        }
+        Ok(())
+    }
+}
+
+fn last_error(function: &'static str) -> Error {
+    Error::Io {
+        function,
+        kind: io::Error::last_os_error().kind(),
+    }
+}
+
+/// What getch() should do after `wait()`.
+pub(crate) enum Wait {
+    Woken,
+    Read,
+    TimedOut,
+    /// A signal arrived, such as the one for resizing, which curses checks for when it reads.
+    Interrupted,
+}
+
+/// Waits for input or a wakeup until the deadline, or without a limit if there is none. Curses may
+/// already hold input that the terminal doesn't report, see `set_pending()`.
+pub(crate) fn wait(deadline: Option<Instant>) -> Wait {
+    let wake_fd = READ_FD.load(Ordering::SeqCst);
+    if wake_fd < 0 {
+        return Wait::Read;
+    }
+    let milliseconds = match deadline {
+        // Round up, so that the wait doesn't end just before the deadline
+        Some(deadline) => {
+            let left = deadline.saturating_duration_since(Instant::now());
+            left.as_micros().div_ceil(1000).min(i32::MAX as u128) as i32
+        }
+        None => -1,
+    };
+    let mut fds = [
+        libc::pollfd {
+            fd: wake_fd,
+            events: libc::POLLIN,
+            revents: 0,
+        },
+        libc::pollfd {
+            fd: input_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        },
+    ];
+    match unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, milliseconds) } {
+        0 => Wait::TimedOut,
+        n if n > 0 && fds[0].revents != 0 => {
+            drain(wake_fd);
+            Wait::Woken
+        }
+        n if n < 0 && io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => {
+            Wait::Interrupted
+        }
+        _ => Wait::Read,
+    }
+}
+
+/// Reads all the wakeups waiting in the pipe.
+fn drain(fd: RawFd) {
+    let mut buffer = [0u8; 64];
+    let pointer = buffer.as_mut_ptr() as *mut libc::c_void;
+    while unsafe { libc::read(fd, pointer, buffer.len()) } > 0 {}
+}
+
+/// Notes that curses may hold input that the terminal no longer reports as readable: input pushed
+/// back with ungetch(), the rest of an escape sequence keypad() couldn't match, or a KEY_RESIZE for
+/// a signal that interrupted the wait.
+pub(crate) fn set_pending() {
+    PENDING.store(true, Ordering::SeqCst);
+}
+
+/// Returns whether curses may hold input, and forgets about it until `set_pending()` again.
+pub(crate) fn take_pending() -> bool {
+    PENDING.swap(false, Ordering::SeqCst)
+}
+
+/// Returns whether a waker has been created, and getch() has to wait for it.
+pub(crate) fn is_enabled() -> bool {
+    READ_FD.load(Ordering::SeqCst) >= 0
+}
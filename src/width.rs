@@ -0,0 +1,107 @@
+use std::borrow::Cow;
+
+use unicode_width::UnicodeWidthChar;
+
+/// Returns the number of terminal columns the string takes up when output.
+///
+/// Wide characters, such as CJK ideographs and most emoji, take up two columns while combining
+/// characters take up none, following the East Asian Width rules of Unicode. Control characters
+/// are counted as zero columns wide.
+///
+/// ```rust
+/// use pancurses::display_width;
+///
+/// assert_eq!(5, display_width("hello"));
+/// assert_eq!(4, display_width("漢字"));
+/// assert_eq!(1, display_width("e\u{301}"));
+/// ```
+pub fn display_width(string: &str) -> usize {
+    string.chars().map(char_width).sum()
+}
+
+/// Returns the number of columns the character takes up, see `display_width()`.
+pub(crate) fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+/// Returns the number of columns curses takes to output the character, which for control
+/// characters is the width of how ncurses shows them: ^[ for ESC and ~E after the lead byte for
+/// U+0085. Tabs are expected to have been expanded with `expand_tabs()`.
+pub(crate) fn output_width(c: char) -> usize {
+    match c {
+        '\n' => 0,
+        '\u{80}'..='\u{9f}' => 3,
+        c if c.is_control() => 2,
+        c => char_width(c),
+    }
+}
+
+/// Replaces each tab with the spaces curses outputs for it when the string is output from the
+/// given column on, which reach up to the next multiple of tab_size.
+pub(crate) fn expand_tabs(string: &str, column: usize, tab_size: usize) -> Cow<'_, str> {
+    if !string.contains('\t') {
+        return Cow::Borrowed(string);
+    }
+    let tab_size = tab_size.max(1);
+    let mut column = column;
+    let mut expanded = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '\t' => {
+                let spaces = tab_size - column % tab_size;
+                expanded.push_str(&" ".repeat(spaces));
+                column += spaces;
+            }
+            '\n' => {
+                expanded.push(c);
+                column = 0;
+            }
+            c => {
+                expanded.push(c);
+                column += output_width(c);
+            }
+        }
+    }
+    Cow::Owned(expanded)
+}
+
+/// Returns the longest prefix of the string that fits into max_cols columns along with its width.
+///
+/// A character is never cut in half, so the prefix may be a column narrower than max_cols when the
+/// next character is a wide one. Combining characters stay with the character they follow.
+fn prefix_within(string: &str, max_cols: usize) -> (&str, usize) {
+    let mut width = 0;
+    for (index, c) in string.char_indices() {
+        let w = output_width(c);
+        if width + w > max_cols {
+            return (&string[..index], width);
+        }
+        width += w;
+    }
+    (string, width)
+}
+
+/// Truncates the string to fit into max_cols columns as measured by `output_width()`, ending it
+/// with the ellipsis if anything was cut off. Returns the truncated string and the number of
+/// columns it takes up.
+pub(crate) fn truncate<'a>(
+    string: &'a str,
+    max_cols: usize,
+    ellipsis: Option<&str>,
+) -> (Cow<'a, str>, usize) {
+    let (prefix, width) = prefix_within(string, max_cols);
+    if prefix.len() == string.len() {
+        return (Cow::Borrowed(string), width);
+    }
+    match ellipsis {
+        Some(ellipsis) => {
+            let (ellipsis, ellipsis_width) = prefix_within(ellipsis, max_cols);
+            let (prefix, width) = prefix_within(string, max_cols - ellipsis_width);
+            (
+                Cow::Owned(format!("{}{}", prefix, ellipsis)),
+                width + ellipsis_width,
+            )
+        }
+        None => (Cow::Borrowed(prefix), width),
+    }
+}
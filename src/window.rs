@@ -1,5 +1,32 @@
-use crate::{chtype, curses, platform_specific, ptr, Input, ToChtype, ERR};
+use crate::error::{check, check_ptr, to_cstring};
+use crate::escape::{self, PASTE_END, PASTE_START};
+#[cfg(all(unix, feature = "async"))]
+use crate::input_stream::InputStream;
+use crate::line_editor::LineEditor;
+use crate::modes;
+#[cfg(unix)]
+use crate::waker::{self, Wait};
+use crate::width::{expand_tabs, truncate};
+use crate::{chtype, curses, platform_specific, ptr, Error, Input, MouseEvent, Result, ToChtype, ERR};
+#[cfg(any(windows, feature = "wide"))]
+use crate::ComplexChar;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
 use std::ffi::CString;
+use std::ops::Deref;
+use std::rc::Rc;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+
+thread_local! {
+    /// The screen of the `Session` that windows are created on, if it opened one with newterm().
+    static SCREEN: RefCell<Option<Rc<platform_specific::Screen>>> = const { RefCell::new(None) };
+}
+
+/// Sets the screen that windows created from now on belong to.
+pub(crate) fn set_screen(screen: Option<Rc<platform_specific::Screen>>) {
+    SCREEN.with(|current| *current.borrow_mut() = screen);
+}
 
 #[derive(Debug)]
 pub struct Window {
@@ -8,36 +35,150 @@ pub struct Window {
     #[cfg(unix)]
     _window: curses::WINDOW,
     _stdscr: bool,
+    /// The screen the window was created on. delscreen() frees the windows of a screen, so each
+    /// window keeps it alive until the window itself has been deleted.
+    _screen: Option<Rc<platform_specific::Screen>>,
+    _control_chars: Cell<ControlChars>,
+    /// The delay set with timeout() or nodelay(), which curses has no way to read back
+    _timeout: Cell<i32>,
+}
+
+/// Decides what the string output functions of a window do with NUL bytes and other control
+/// characters, such as ESC, in the strings they are given.
+///
+/// Newlines and tabs are not affected, as curses uses them to move the cursor.
+///
+/// ```rust
+/// use pancurses::{endwin, initscr, ControlChars, Error, A_CHARTEXT};
+///
+/// let window = initscr();
+/// window.set_control_chars(ControlChars::Replace('?'));
+/// window.mvaddstr(0, 0, "a\0b\x1b[2J").unwrap();
+/// assert_eq!('?', (window.mvinch(0, 1) & A_CHARTEXT) as u8 as char);
+/// assert_eq!('?', (window.mvinch(0, 3) & A_CHARTEXT) as u8 as char);
+///
+/// window.set_control_chars(ControlChars::Reject);
+/// assert_eq!(
+///     Err(Error::ControlCharacter {
+///         function: "waddstr",
+///         position: 1,
+///         character: '\x1b',
+///     }),
+///     window.addstr("a\x1b[2J")
+/// );
+/// endwin();
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ControlChars {
+    /// Control characters are passed on to curses. A NUL byte can't be passed on, so it's reported
+    /// as `Error::InteriorNul`. This is the default.
+    #[default]
+    Passthrough,
+    /// Nothing is output if the string contains control characters, `Error::ControlCharacter`
+    /// is returned instead.
+    Reject,
+    /// Every control character is output as the given replacement character.
+    Replace(char),
 }
 
 #[cfg(windows)]
-type WindowPointer = *mut curses::WINDOW;
+pub(crate) type WindowPointer = *mut curses::WINDOW;
 #[cfg(unix)]
-type WindowPointer = curses::WINDOW;
+pub(crate) type WindowPointer = curses::WINDOW;
 
 impl Window {
     /// Adds the chtype ch to the window at the current cursor position, and advances the cursor.
     ///
     /// Note that chtypes can convey both text (a single character) and attributes, including a
     /// color pair.
-    pub fn addch<T: ToChtype>(&self, ch: T) -> i32 {
-        unsafe { curses::waddch(self._window, ch.to_chtype()) }
+    pub fn addch<T: ToChtype>(&self, ch: T) -> Result<()> {
+        check("waddch", unsafe {
+            curses::waddch(self._window, ch.to_chtype())
+        })
+    }
+
+    /// Adds the complex character wch to the window at the current cursor position, and advances
+    /// the cursor.
+    ///
+    /// This is the wide-character version of addch(), it can add any Unicode character along with
+    /// combining characters, attributes and a color pair.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, ComplexChar, A_BOLD};
+    ///
+    /// let window = initscr();
+    /// let wch = ComplexChar::new("漢", A_BOLD, 0).unwrap();
+    /// window.mv(0, 0).unwrap();
+    /// window.add_wch(&wch).unwrap();
+    /// assert_eq!(Ok(wch), window.mvin_wch(0, 0));
+    /// endwin();
+    /// ```
+    #[cfg(any(windows, feature = "wide"))]
+    pub fn add_wch(&self, wch: &ComplexChar) -> Result<()> {
+        check("wadd_wch", platform_specific::_wadd_wch(self._window, wch))
     }
 
     /// Write all the characters of the string to the given window.
     ///
     /// The functionality is similar to calling window.addch() once for each character in the
     /// string.
-	pub fn addstr<T: AsRef<str>>(&self, string: T) -> i32 {
-		let s = CString::new(string.as_ref()).unwrap();
-        unsafe { curses::waddstr(self._window, s.as_ptr()) }
+    pub fn addstr<T: AsRef<str>>(&self, string: T) -> Result<()> {
+        let s = self.to_output_cstring("waddstr", string.as_ref())?;
+        check("waddstr", unsafe {
+            curses::waddstr(self._window, s.as_ptr())
+        })
     }
 
     /// Write at most length characters; if length is negative, then the entire string will be
     /// added.
-    pub fn addnstr<T: AsRef<str>>(&self, string: T, length: usize) -> i32 {
-        let s = CString::new(string.as_ref()).unwrap();
-        unsafe { curses::waddnstr(self._window, s.as_ptr(), length as i32) }
+    ///
+    /// The length doesn't take the width of the characters into account, use addstr_cols() to
+    /// limit the number of columns the string takes up instead.
+    pub fn addnstr<T: AsRef<str>>(&self, string: T, length: usize) -> Result<()> {
+        let s = self.to_output_cstring("waddnstr", string.as_ref())?;
+        check("waddnstr", unsafe {
+            curses::waddnstr(self._window, s.as_ptr(), length as i32)
+        })
+    }
+
+    /// Write as much of the string as fits into max_cols terminal columns, and return the number
+    /// of columns that were used.
+    ///
+    /// Wide characters take up two columns, see `display_width()`. If the string doesn't fit, it's
+    /// cut off at a character boundary and the ellipsis, if one is given, is added in place of the
+    /// cut off part, still within max_cols columns. The string is expected to fit on one line.
+    ///
+    /// The control character setting of the window is applied before the string is measured, and
+    /// tabs count as the spaces up to the next tab stop from the cursor. Control characters that
+    /// are passed on count as the columns ncurses shows them in, such as ^[ for ESC.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, ControlChars};
+    ///
+    /// let window = initscr();
+    /// window.mv(0, 0).unwrap();
+    /// assert_eq!(Ok(4), window.addstr_cols("漢字漢字", 5, None));
+    /// assert_eq!(Ok(6), window.addstr_cols("漢字 kanji", 6, Some("…")));
+    /// assert_eq!(Ok(2), window.addstr_cols("ok", 6, Some("…")));
+    ///
+    /// window.set_control_chars(ControlChars::Replace('?'));
+    /// window.mv(1, 0).unwrap();
+    /// assert_eq!(Ok(5), window.addstr_cols("a\x1b[2J", 5, None));
+    /// assert_eq!((1, 5), window.get_cur_yx());
+    /// window.mv(2, 2).unwrap();
+    /// assert_eq!(Ok(7), window.addstr_cols("\tab", 7, Some("...")));
+    /// assert_eq!((2, 9), window.get_cur_yx());
+    /// endwin();
+    /// ```
+    pub fn addstr_cols<T: AsRef<str>>(
+        &self,
+        string: T,
+        max_cols: usize,
+        ellipsis: Option<&str>,
+    ) -> Result<usize> {
+        let x = self.get_cur_x();
+        let (s, width) = self.fit_cols("waddstr", string.as_ref(), x, max_cols, ellipsis)?;
+        self.addstr(s).map(|_| width)
     }
 
     /// Retrieve attributes for the given window.
@@ -65,23 +206,32 @@ impl Window {
     }
 
     /// Turns off the named attributes without affecting any other attributes.
-    pub fn attroff<T: Into<chtype>>(&self, attributes: T) -> i32 {
-        platform_specific::_attroff(self._window, attributes.into())
+    pub fn attroff<T: Into<chtype>>(&self, attributes: T) -> Result<()> {
+        check(
+            "wattroff",
+            platform_specific::_attroff(self._window, attributes.into()),
+        )
     }
 
     /// Turns on the named attributes without affecting any other attributes.
-    pub fn attron<T: Into<chtype>>(&self, attributes: T) -> i32 {
-        platform_specific::_attron(self._window, attributes.into())
+    pub fn attron<T: Into<chtype>>(&self, attributes: T) -> Result<()> {
+        check(
+            "wattron",
+            platform_specific::_attron(self._window, attributes.into()),
+        )
     }
 
     /// Sets the current attributes of the given window to attributes.
-    pub fn attrset<T: Into<chtype>>(&self, attributes: T) -> i32 {
-        platform_specific::_attrset(self._window, attributes.into())
+    pub fn attrset<T: Into<chtype>>(&self, attributes: T) -> Result<()> {
+        check(
+            "wattrset",
+            platform_specific::_attrset(self._window, attributes.into()),
+        )
     }
 
     /// Not only change the background, but apply it immediately to every cell in the window.
-    pub fn bkgd<T: Into<chtype>>(&self, ch: T) -> i32 {
-        unsafe { curses::wbkgd(self._window, ch.into()) }
+    pub fn bkgd<T: Into<chtype>>(&self, ch: T) -> Result<()> {
+        check("wbkgd", unsafe { curses::wbkgd(self._window, ch.into()) })
     }
 
     /// Manipulate the background of a window. The background is a chtype consisting of any
@@ -105,8 +255,8 @@ impl Window {
         top_right_corner: T,
         bottom_left_corner: T,
         bottom_right_corner: T,
-    ) -> i32 {
-        unsafe {
+    ) -> Result<()> {
+        check("wborder", unsafe {
             curses::wborder(
                 self._window,
                 left_side.to_chtype(),
@@ -118,44 +268,50 @@ impl Window {
                 bottom_left_corner.to_chtype(),
                 bottom_right_corner.to_chtype(),
             )
-        }
+        })
     }
 
     /// Changes the attributes of a given number of characters starting at the current cursor
     /// location. It does not update the cursor and does not perform wrapping. A character count
     /// of -1 or greater than the remaining window width means to change attributes all the way
     /// to the end of the current line.
-    pub fn chgat(&self, n: i32, attributes: chtype, color_pair: i16) -> i32 {
-        unsafe { curses::wchgat(self._window, n, attributes, color_pair, ptr::null_mut()) }
+    pub fn chgat(&self, n: i32, attributes: chtype, color_pair: i16) -> Result<()> {
+        check("wchgat", unsafe {
+            curses::wchgat(self._window, n, attributes, color_pair, ptr::null_mut())
+        })
     }
 
     /// Similar to erase(), but also calls clearok() to ensure that the the window is cleared on
     /// the next refresh().
-    pub fn clear(&self) -> i32 {
-        unsafe { curses::wclear(self._window) }
+    pub fn clear(&self) -> Result<()> {
+        check("wclear", unsafe { curses::wclear(self._window) })
     }
 
     /// With clearok(), if bf is TRUE, the next call to refresh() with
     /// this window will clear the screen completely and redraw the
     /// entire screen.
-    pub fn clearok(&self, bf: bool) -> i32 {
-        unsafe { curses::clearok(self._window, bf as u8) }
+    pub fn clearok(&self, bf: bool) -> Result<()> {
+        check("clearok", unsafe {
+            curses::clearok(self._window, bf as u8)
+        })
     }
 
     /// Clear the window from the current cursor position to the end of the window.
-    pub fn clrtobot(&self) -> i32 {
-        unsafe { curses::wclrtobot(self._window) }
+    pub fn clrtobot(&self) -> Result<()> {
+        check("wclrtobot", unsafe { curses::wclrtobot(self._window) })
     }
 
     /// Clear the window from the current cursor position to the end of the current line.
-    pub fn clrtoeol(&self) -> i32 {
-        unsafe { curses::wclrtoeol(self._window) }
+    pub fn clrtoeol(&self) -> Result<()> {
+        check("wclrtoeol", unsafe { curses::wclrtoeol(self._window) })
     }
 
     /// Sets the current color of the given window to the foreground/background combination
     /// described by the color pair parameter.
-    pub fn color_set(&self, color_pair: i16) -> i32 {
-        unsafe { curses::wcolor_set(self._window, color_pair, ptr::null_mut()) }
+    pub fn color_set(&self, color_pair: i16) -> Result<()> {
+        check("wcolor_set", unsafe {
+            curses::wcolor_set(self._window, color_pair, ptr::null_mut())
+        })
     }
 
     /// Copy all text from this window to the destination window. The arguments src_tc and
@@ -175,8 +331,8 @@ impl Window {
         dst_br: i32,
         dst_bc: i32,
         overlay: bool,
-    ) -> i32 {
-        unsafe {
+    ) -> Result<()> {
+        check("copywin", unsafe {
             curses::copywin(
                 self._window,
                 destination_window._window,
@@ -188,35 +344,37 @@ impl Window {
                 dst_bc,
                 overlay as i32,
             )
-        }
+        })
     }
 
     /// Delete the character under the cursor. All characters to the right of the cursor
     /// on the same line are moved to the left one position and hte last character on the
     /// line is filled with a blank. The cursor position does not change.
-    pub fn delch(&self) -> i32 {
-        unsafe { curses::wdelch(self._window) }
+    pub fn delch(&self) -> Result<()> {
+        check("wdelch", unsafe { curses::wdelch(self._window) })
     }
 
     /// Delete the line under the cursor. All lines below are moved up one line, and the
     /// bottom line is cleared. The cursor position does not change.
-    pub fn deleteln(&self) -> i32 {
-        unsafe { curses::wdeleteln(self._window) }
+    pub fn deleteln(&self) -> Result<()> {
+        check("wdeleteln", unsafe { curses::wdeleteln(self._window) })
     }
 
     /// Deletes the window, freeing all associated memory. In the case of overlapping windows,
     /// subwindows should be deleted before the main window.
-    pub fn delwin(self) -> i32 {
+    pub fn delwin(mut self) -> Result<()> {
         let r = unsafe { curses::delwin(self._window) };
+        let screen = self._screen.take();
         std::mem::forget(self);
-        r
+        drop(screen);
+        check("delwin", r)
     }
 
     /// The same as subwin(), except that begy and begx are relative to the origin of the window
     /// rather than the screen.
     ///
     /// There is no difference between subwindows and derived windows.
-    pub fn derwin(&self, nlines: i32, ncols: i32, begy: i32, begx: i32) -> Result<Window, i32> {
+    pub fn derwin(&self, nlines: i32, ncols: i32, begy: i32, begx: i32) -> Result<SubWindow<'_>> {
         self.subwin(
             nlines,
             ncols,
@@ -227,17 +385,20 @@ impl Window {
 
     /// Draw a border around the edge of the window. If any argument is zero, an appropriate
     /// default is used.
-    pub fn draw_box<T: ToChtype>(&self, verch: T, horch: T) -> i32 {
-        platform_specific::_draw_box(self._window, verch.to_chtype(), horch.to_chtype())
+    pub fn draw_box<T: ToChtype>(&self, verch: T, horch: T) -> Result<()> {
+        check(
+            "box",
+            platform_specific::_draw_box(self._window, verch.to_chtype(), horch.to_chtype()),
+        )
     }
 
     /// Creates an exact duplicate of the window.
-    pub fn dupwin(&self) -> Window {
-        let dup_win = unsafe { curses::dupwin(self._window) };
-        Window {
-            _window: dup_win,
-            _stdscr: false,
-        }
+    pub fn dupwin(&self) -> Result<Window> {
+        let dup_win = check_ptr("dupwin", unsafe { curses::dupwin(self._window) })?;
+        let window = new_window(dup_win, false);
+        window._control_chars.set(self._control_chars.get());
+        window._timeout.set(self._timeout.get());
+        Ok(window)
     }
 
     /// Reports whether the given screen-relative y, x coordinates fall within the window.
@@ -246,8 +407,8 @@ impl Window {
     }
 
     /// Copies blanks (i.e. the background chtype) to every cell of the window.
-    pub fn erase(&self) -> i32 {
-        unsafe { curses::werase(self._window) }
+    pub fn erase(&self) -> Result<()> {
+        check("werase", unsafe { curses::werase(self._window) })
     }
 
     /// Get the upper-left y coordinate of this window
@@ -281,8 +442,145 @@ impl Window {
     /// returned instead of the raw characters.
     /// If nodelay(win, TRUE) has been called on the window and no input is waiting, None is
     /// returned.
+    ///
+    /// An escape character is returned as `Input::Character('\x1b')`. With `set_alt_decoding()`,
+    /// or one of the terminal modes that report escape sequences, a lone one is returned as
+    /// `Input::KeyEscape` instead, see there. With `set_alt_decoding()` one followed by more input
+    /// within the escape delay is returned as that input with the Alt modifier.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, set_alt_decoding, Input, Key, Modifiers};
+    ///
+    /// let window = initscr();
+    /// window.keypad(true).unwrap();
+    /// set_alt_decoding(true);
+    /// let alt_x = Input::KeyModified(Key::Character('x'), Modifiers::ALT);
+    /// window.ungetch(&alt_x).unwrap();
+    /// assert_eq!(Some(alt_x), window.getch());
+    /// endwin();
+    /// ```
     pub fn getch(&self) -> Option<Input> {
-        platform_specific::_wgetch(self._window)
+        self.read_input(platform_specific::_wgetch)
+    }
+
+    /// Reads input with getch() or get_wch() and decodes it.
+    fn read_input(&self, read: fn(WindowPointer) -> Option<Input>) -> Option<Input> {
+        let input = match self.wait_for_wakeup(read) {
+            Some(input) => input,
+            None => read(self._window),
+        };
+        #[cfg(unix)]
+        {
+            if input == Some(Input::Character('\x1b')) {
+                waker::set_pending();
+            }
+        }
+        decode_mouse(self.decode_escape(input, read))
+    }
+
+    /// Waits for input or a wakeup from a `Waker`, returning the input or `Input::Wakeup` unless
+    /// the input is left to be read.
+    #[cfg(unix)]
+    fn wait_for_wakeup(&self, read: fn(WindowPointer) -> Option<Input>) -> Option<Option<Input>> {
+        if !waker::is_enabled() {
+            return None;
+        }
+        let timeout = self._timeout.get();
+        let deadline = if timeout < 0 {
+            None
+        } else {
+            Some(Instant::now() + Duration::from_millis(timeout as u64))
+        };
+        loop {
+            // Input that curses already holds doesn't make the terminal readable, so it's read
+            // without waiting instead
+            if waker::take_pending() {
+                unsafe { curses::wtimeout(self._window, 0) };
+                let input = read(self._window);
+                unsafe { curses::wtimeout(self._window, timeout) };
+                if input.is_some() {
+                    waker::set_pending();
+                    return Some(input);
+                }
+            }
+            match waker::wait(deadline) {
+                Wait::Woken => return Some(Some(Input::Wakeup)),
+                Wait::TimedOut => return Some(None),
+                Wait::Read => return None,
+                Wait::Interrupted => waker::set_pending(),
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    fn wait_for_wakeup(&self, _: fn(WindowPointer) -> Option<Input>) -> Option<Option<Input>> {
+        None
+    }
+
+    /// Decodes an escape character and the input that follows it, see `set_alt_decoding()`.
+    fn decode_escape(
+        &self,
+        input: Option<Input>,
+        read: fn(WindowPointer) -> Option<Input>,
+    ) -> Option<Input> {
+        let alt = escape::alt_decoding();
+        let sequences = modes::reports_sequences();
+        if input != Some(Input::Character('\x1b')) || !(alt || sequences) {
+            return escape::decode(input, &mut WindowSource(self, read), alt, sequences);
+        }
+        let wait = platform_specific::_escape_wait(self._window);
+        unsafe { curses::wtimeout(self._window, wait) };
+        let decoded = escape::decode(input, &mut WindowSource(self, read), alt, sequences);
+        unsafe { curses::wtimeout(self._window, self._timeout.get()) };
+        decoded
+    }
+
+    /// Read a character from the terminal associated with the window as a wide character.
+    ///
+    /// This works like getch(), but on ncurses the character is decoded by curses according to
+    /// the locale instead of being assembled from UTF-8 bytes.
+    #[cfg(any(windows, feature = "wide"))]
+    pub fn get_wch(&self) -> Option<Input> {
+        self.read_input(platform_specific::_wget_wch)
+    }
+
+    /// Returns the input of the window as a `futures_core::Stream`, for waiting for input along
+    /// with other events in async code instead of blocking the thread in getch().
+    ///
+    /// Must be called from within a tokio runtime. See `InputStream` for the details.
+    ///
+    /// (Only supported on Unix, with the `async` feature)
+    ///
+    /// ```rust,edition2018,no_run
+    /// use pancurses::{endwin, initscr, Input};
+    /// use std::future::poll_fn;
+    /// use std::pin::Pin;
+    /// use std::time::Duration;
+    /// use futures_core::Stream;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let window = initscr();
+    ///     window.keypad(true).unwrap();
+    ///     let mut input = window.input_stream().unwrap();
+    ///     let mut ticks = tokio::time::interval(Duration::from_secs(1));
+    ///     loop {
+    ///         tokio::select! {
+    ///             key = poll_fn(|cx| Pin::new(&mut input).poll_next(cx)) => match key {
+    ///                 Some(Input::Character('q')) | None => break,
+    ///                 Some(key) => { window.printw(format!("{:?} ", key)).unwrap(); }
+    ///             },
+    ///             _ = ticks.tick() => { window.printw(".").unwrap(); }
+    ///         }
+    ///         window.refresh().unwrap();
+    ///     }
+    ///     drop(input);
+    ///     endwin();
+    /// }
+    /// ```
+    #[cfg(all(unix, feature = "async"))]
+    pub fn input_stream(&self) -> Result<InputStream<'_>> {
+        InputStream::new(self, self._timeout.get())
     }
 
     /// Return the current x coordinate of the cursor
@@ -317,21 +615,23 @@ impl Window {
 
     /// Draw a horizontal line using ch from the current cursor position. The line is at most
     /// n characters long, or as many as fit into the window.
-    pub fn hline<T: ToChtype>(&self, ch: T, n: i32) -> i32 {
-        unsafe { curses::whline(self._window, ch.to_chtype(), n) }
+    pub fn hline<T: ToChtype>(&self, ch: T, n: i32) -> Result<()> {
+        check("whline", unsafe {
+            curses::whline(self._window, ch.to_chtype(), n)
+        })
     }
 
     /// For positive n, insert n lines into the specified window above the current line.
     /// The n bottom lines are lost. For negative n, delete n lines (starting with the one under
     /// the cursor), and move the remaining lines up. The bottom n lines are cleared.
     /// The current cursor position remains the same.
-    pub fn insdelln(&self, n: i32) -> i32 {
-        unsafe { curses::winsdelln(self._window, n) }
+    pub fn insdelln(&self, n: i32) -> Result<()> {
+        check("winsdelln", unsafe { curses::winsdelln(self._window, n) })
     }
 
     /// A blank line is inserted above the current line and the bottom line is lost.
-    pub fn insertln(&self) -> i32 {
-        unsafe { curses::winsertln(self._window) }
+    pub fn insertln(&self) -> Result<()> {
+        check("winsertln", unsafe { curses::winsertln(self._window) })
     }
 
     /// Returns true if the specified line in the specified window has been changed since the last
@@ -350,8 +650,15 @@ impl Window {
     ///
     /// Per X/Open, the default for keypad mode is OFF. You'll probably want it on. With keypad
     /// mode off, if a special key is pressed, getch() does nothing or returns ERR.
-    pub fn keypad(&self, use_keypad: bool) -> i32 {
-        unsafe { curses::keypad(self._window, use_keypad as u8) }
+    pub fn keypad(&self, use_keypad: bool) -> Result<()> {
+        check("keypad", unsafe {
+            curses::keypad(self._window, use_keypad as u8)
+        })?;
+        if use_keypad && modes::mouse_tracking() {
+            // Curses may only now learn about its key codes, the mouse included
+            check("keypad", platform_specific::_set_mouse_decoding(false))?;
+        }
+        Ok(())
     }
 
     /// Insert the character ch before the character under the cursor.
@@ -359,8 +666,26 @@ impl Window {
     /// All characters to the right of the cursor are moved one space to the right, with the
     /// possibility of the rightmost character on the line being lost. The insertion operation does
     /// not change the cursor position.
-    pub fn insch<T: ToChtype>(&self, ch: T) -> i32 {
-        unsafe { curses::winsch(self._window, ch.to_chtype()) }
+    pub fn insch<T: ToChtype>(&self, ch: T) -> Result<()> {
+        check("winsch", unsafe {
+            curses::winsch(self._window, ch.to_chtype())
+        })
+    }
+
+    /// Returns the complex character at the current cursor position.
+    #[cfg(any(windows, feature = "wide"))]
+    pub fn in_wch(&self) -> Result<ComplexChar> {
+        platform_specific::_win_wch(self._window).map_err(|_| Error::Curses {
+            function: "win_wch",
+        })
+    }
+
+    /// Insert the complex character wch before the character under the cursor.
+    ///
+    /// This is the wide-character version of insch(), the cursor position does not change.
+    #[cfg(any(windows, feature = "wide"))]
+    pub fn ins_wch(&self, wch: &ComplexChar) -> Result<()> {
+        check("wins_wch", platform_specific::_wins_wch(self._window, wch))
     }
 
     /// Converts between screen-relative and window-relative coordinates.
@@ -370,7 +695,9 @@ impl Window {
     pub fn mouse_trafo(&self, y: i32, x: i32, to_screen: bool) -> (i32, i32) {
         let mut mut_y = y;
         let mut mut_x = x;
-        unsafe { curses::wmouse_trafo(self._window, &mut mut_y, &mut mut_x, to_screen as u8); }
+        unsafe {
+            curses::wmouse_trafo(self._window, &mut mut_y, &mut mut_x, to_screen as u8);
+        }
         (mut_y, mut_x)
     }
 
@@ -378,34 +705,69 @@ impl Window {
     ///
     /// This does not move the physical cursor of the terminal until refresh() is called.  The
     /// position specified is relative to the upper left corner of the window, which is (0,0).
-    pub fn mv(&self, y: i32, x: i32) -> i32 {
-        unsafe { curses::wmove(self._window, y, x) }
+    pub fn mv(&self, y: i32, x: i32) -> Result<()> {
+        self.check_move("wmove", y, x, unsafe { curses::wmove(self._window, y, x) })
     }
 
     /// moves the cursor to the specified position and adds ch to the specified window
-    pub fn mvaddch<T: ToChtype>(&self, y: i32, x: i32, ch: T) -> i32 {
-        unsafe { curses::mvwaddch(self._window, y, x, ch.to_chtype()) }
+    pub fn mvaddch<T: ToChtype>(&self, y: i32, x: i32, ch: T) -> Result<()> {
+        self.check_move("mvwaddch", y, x, unsafe {
+            curses::mvwaddch(self._window, y, x, ch.to_chtype())
+        })
+    }
+
+    /// Moves the cursor to the specified position and adds the complex character wch to the window.
+    #[cfg(any(windows, feature = "wide"))]
+    pub fn mvadd_wch(&self, y: i32, x: i32, wch: &ComplexChar) -> Result<()> {
+        self.check_move("mvwadd_wch", y, x, {
+            platform_specific::_mvwadd_wch(self._window, y, x, wch)
+        })
     }
 
     /// Write all the characters of the string str to the given window. The functionality is
     /// similar to calling waddch() once for each character in the string.
-    pub fn mvaddstr<T: AsRef<str>>(&self, y: i32, x: i32, string: T) -> i32 {
-        let s = CString::new(string.as_ref()).unwrap();
-        unsafe { curses::mvwaddstr(self._window, y, x, s.as_ptr()) }
+    pub fn mvaddstr<T: AsRef<str>>(&self, y: i32, x: i32, string: T) -> Result<()> {
+        let s = self.to_output_cstring("mvwaddstr", string.as_ref())?;
+        self.check_move("mvwaddstr", y, x, unsafe {
+            curses::mvwaddstr(self._window, y, x, s.as_ptr())
+        })
     }
 
     /// Write the first'n' characters of the string str to the given window.
-    pub fn mvaddnstr<T: AsRef<str>>(&self, y: i32, x: i32, string: T, n: i32) -> i32 {
-        let s = CString::new(string.as_ref()).unwrap();
-        unsafe { curses::mvwaddnstr(self._window, y, x, s.as_ptr(), n) }
+    pub fn mvaddnstr<T: AsRef<str>>(&self, y: i32, x: i32, string: T, n: i32) -> Result<()> {
+        let s = self.to_output_cstring("mvwaddnstr", string.as_ref())?;
+        self.check_move("mvwaddnstr", y, x, unsafe {
+            curses::mvwaddnstr(self._window, y, x, s.as_ptr(), n)
+        })
+    }
+
+    /// Move the cursor and write as much of the string as fits into max_cols terminal columns,
+    /// returning the number of columns that were used. See addstr_cols().
+    pub fn mvaddstr_cols<T: AsRef<str>>(
+        &self,
+        y: i32,
+        x: i32,
+        string: T,
+        max_cols: usize,
+        ellipsis: Option<&str>,
+    ) -> Result<usize> {
+        let (s, width) = self.fit_cols("mvwaddstr", string.as_ref(), x, max_cols, ellipsis)?;
+        self.mvaddstr(y, x, s).map(|_| width)
     }
 
     /// Moves the cursor and changes the attributes of a given number of characters starting at the
     /// cursor location. It does not update the cursor and does not perform wrapping. A character count
     /// of -1 or greater than the remaining window width means to change attributes all the way
     /// to the end of the current line.
-    pub fn mvchgat(&self, y: i32, x: i32, n: i32, attributes: chtype, color_pair: i16) -> i32 {
-        unsafe {
+    pub fn mvchgat(
+        &self,
+        y: i32,
+        x: i32,
+        n: i32,
+        attributes: chtype,
+        color_pair: i16,
+    ) -> Result<()> {
+        self.check_move("mvwchgat", y, x, unsafe {
             curses::mvwchgat(
                 self._window,
                 y,
@@ -415,15 +777,7 @@ impl Window {
                 color_pair,
                 ptr::null_mut(),
             )
-        }
-    }
-
-    /// Moves a derived window (or subwindow) inside its parent window.
-    ///
-    /// The screen-relative parameters of the window are not changed. This routine is used to
-    /// display different parts of the parent window at the same physical position on the screen.
-    pub fn mvderwin(&self, pary: i32, parx: i32) -> i32 {
-        unsafe { curses::mvderwin(self._window, pary, parx) }
+        })
     }
 
     /// Retrieves the character and attribute from the specified window position, in the form of a
@@ -432,60 +786,123 @@ impl Window {
         unsafe { curses::mvwinch(self._window, y, x) }
     }
 
+    /// Returns the complex character at the specified position.
+    #[cfg(any(windows, feature = "wide"))]
+    pub fn mvin_wch(&self, y: i32, x: i32) -> Result<ComplexChar> {
+        platform_specific::_mvwin_wch(self._window, y, x)
+            .map_err(|_| self.move_error("mvwin_wch", y, x))
+    }
+
+    /// Move the cursor and then insert the complex character wch before the character under the
+    /// cursor.
+    #[cfg(any(windows, feature = "wide"))]
+    pub fn mvins_wch(&self, y: i32, x: i32, wch: &ComplexChar) -> Result<()> {
+        self.check_move("mvwins_wch", y, x, {
+            platform_specific::_mvwins_wch(self._window, y, x, wch)
+        })
+    }
+
     /// Move the cursor and then insert the character ch before the character under the cursor.
     ///
     /// First performs a cursor movement using wmove, and returns an error if the position is
     /// outside the window. All characters to the right of the cursor are moved one space to the
     /// right, with the possibility of the rightmost character on the line being lost. The insertion
     /// operation does not change the cursor position.
-    pub fn mvinsch<T: ToChtype>(&self, y: i32, x: i32, ch: T) -> i32 {
-        unsafe { curses::mvwinsch(self._window, y, x, ch.to_chtype()) }
+    pub fn mvinsch<T: ToChtype>(&self, y: i32, x: i32, ch: T) -> Result<()> {
+        self.check_move("mvwinsch", y, x, unsafe {
+            curses::mvwinsch(self._window, y, x, ch.to_chtype())
+        })
     }
 
     /// Add a string to the window at the specified cursor position.
-    pub fn mvprintw<T: AsRef<str>>(&self, y: i32, x: i32, string: T) -> i32 {
-        let s = CString::new(string.as_ref()).unwrap();
-        unsafe { curses::mvwprintw(self._window, y, x, s.as_ptr()) }
+    ///
+    /// Unlike C's `mvwprintw()` the string is not used as a format string, so `%` sequences are
+    /// added as they are. This makes it the same as `mvaddstr()`, use the `mvwprintw!` macro for
+    /// formatted output.
+    pub fn mvprintw<T: AsRef<str>>(&self, y: i32, x: i32, string: T) -> Result<()> {
+        self.mvaddstr(y, x, string)
     }
 
     /// Moves the window so that the upper left-hand corner is at position (y,x).
     ///
     /// If the move would cause the window to be off the screen, it is an error and the window is
     /// not moved. Moving subwindows is allowed.
-    pub fn mvwin(&self, y: i32, x: i32) -> i32 {
-        unsafe { curses::mvwin(self._window, y, x) }
+    pub fn mvwin(&self, y: i32, x: i32) -> Result<()> {
+        check("mvwin", unsafe { curses::mvwin(self._window, y, x) })
     }
 
     /// Controls whether wgetch() is a non-blocking call. If the option is enabled, and
     /// no input is ready, wgetch() will return ERR. If disabled, wgetch() will hang until input is
     /// ready.
-    pub fn nodelay(&self, enabled: bool) -> i32 {
-        unsafe { curses::nodelay(self._window, enabled as u8) as i32 }
+    pub fn nodelay(&self, enabled: bool) -> Result<()> {
+        check("nodelay", unsafe {
+            curses::nodelay(self._window, enabled as u8) as i32
+        })?;
+        self._timeout.set(if enabled { 0 } else { -1 });
+        Ok(())
     }
 
     /// Copies the window to the virtual screen.
-    pub fn noutrefresh(&self) -> i32 {
-        unsafe { curses::wnoutrefresh(self._window) }
+    pub fn noutrefresh(&self) -> Result<()> {
+        check("wnoutrefresh", unsafe {
+            curses::wnoutrefresh(self._window)
+        })
     }
 
     /// Overlays this window on top of destination_window. This window and destination_window are
     /// not required to be the same size; only text where the two windows overlap is copied.
     /// overlay() is non-destructive.
-    pub fn overlay(&self, destination_window: &Window) -> i32 {
-        unsafe { curses::overlay(self._window, destination_window._window) }
+    pub fn overlay(&self, destination_window: &Window) -> Result<()> {
+        check("overlay", unsafe {
+            curses::overlay(self._window, destination_window._window)
+        })
     }
 
     /// Overlays this window on top of destination_window. This window and destination_window are
     /// not required to be the same size; only text where the two windows overlap is copied.
     /// overwrite() is destructive.
-    pub fn overwrite(&self, destination_window: &Window) -> i32 {
-        unsafe { curses::overwrite(self._window, destination_window._window) }
+    pub fn overwrite(&self, destination_window: &Window) -> Result<()> {
+        check("overwrite", unsafe {
+            curses::overwrite(self._window, destination_window._window)
+        })
     }
 
     /// Add a string to the window at the current cursor position.
-    pub fn printw<T: AsRef<str>>(&self, string: T) -> i32 {
-        let s = CString::new(string.as_ref()).unwrap();
-        unsafe { curses::wprintw(self._window, s.as_ptr()) }
+    ///
+    /// Unlike C's `wprintw()` the string is not used as a format string, so `%` sequences are
+    /// added as they are. This makes it the same as `addstr()`, use the `wprintw!` macro for
+    /// formatted output.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, A_CHARTEXT};
+    /// let window = initscr();
+    /// window.printw("%s%n").unwrap();
+    /// assert_eq!('%', (window.mvinch(0, 0) & A_CHARTEXT) as u8 as char);
+    /// assert_eq!('n', (window.mvinch(0, 3) & A_CHARTEXT) as u8 as char);
+    /// endwin();
+    /// ```
+    pub fn printw<T: AsRef<str>>(&self, string: T) -> Result<()> {
+        self.addstr(string)
+    }
+
+    /// Reads a line of at most max characters from the window, with the line editing of
+    /// `LineEditor` but without a history or a completer.
+    ///
+    /// Returns None if reading the line is cancelled with Esc. The line is read with blocking
+    /// reads, see `LineEditor::read_line()`.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr, noecho, Input};
+    ///
+    /// let window = initscr();
+    /// noecho().unwrap();
+    /// window.ungetch(&Input::KeyEscape).unwrap();
+    /// window.ungetch(&Input::Character('a')).unwrap();
+    /// assert_eq!(None, window.read_line(80));
+    /// endwin();
+    /// ```
+    pub fn read_line(&self, max: usize) -> Option<String> {
+        LineEditor::new().read_line(self, max)
     }
 
     /// Copies the named window to the physical terminal screen, taking into account what
@@ -494,27 +911,33 @@ impl Window {
     /// This function must be called to get any output on the terminal, as other routines only
     /// manipulate data structures. Unless leaveok() has been enabled, the physical cursor of the
     /// terminal is left at the location of the window's cursor.
-    pub fn refresh(&self) -> i32 {
-        unsafe { curses::wrefresh(self._window) }
+    pub fn refresh(&self) -> Result<()> {
+        check("wrefresh", unsafe { curses::wrefresh(self._window) })
     }
-    
+
     /// Resizes the window to the given dimensions. Doesn't resize subwindows on pdcurses
-    /// so you have to resize them yourself.
-    pub fn resize(&mut self, nlines: i32, ncols: i32) -> i32 {
-        unsafe { curses::wresize(self._window, nlines, ncols) }
+    /// so you have to resize them yourself, or register them with a `ResizeManager`.
+    pub fn resize(&self, nlines: i32, ncols: i32) -> Result<()> {
+        check("wresize", unsafe {
+            curses::wresize(self._window, nlines, ncols)
+        })
     }
 
     /// If enabled and a scrolling region is set with setscrreg(), any attempt to move off
     /// the bottom margin will cause all lines in the scrolling region to scroll up one line.
-    pub fn scrollok(&self, bf: bool) -> i32 {
-        unsafe { curses::scrollok(self._window, bf as u8) }
+    pub fn scrollok(&self, bf: bool) -> Result<()> {
+        check("scrollok", unsafe {
+            curses::scrollok(self._window, bf as u8)
+        })
     }
 
     /// Sets a scrolling region in a window.
     ///
     /// "top" and "bot" are the line numbers for the top and bottom margins.
-    pub fn setscrreg(&self, top: i32, bot: i32) -> i32 {
-        unsafe { curses::wsetscrreg(self._window, top, bot) }
+    pub fn setscrreg(&self, top: i32, bot: i32) -> Result<()> {
+        check("wsetscrreg", unsafe {
+            curses::wsetscrreg(self._window, top, bot)
+        })
     }
 
     /// Creates a new subwindow within a window.
@@ -523,16 +946,18 @@ impl Window {
     /// position (begy, begx) on the screen. This position is relative to the screen, and not to
     /// the window orig. Changes made to either window will affect both. When using this routine,
     /// you will often need to call touchwin() before calling refresh().
-    pub fn subwin(&self, nlines: i32, ncols: i32, begy: i32, begx: i32) -> Result<Window, i32> {
-        let new_window = unsafe { curses::subwin(self._window, nlines, ncols, begy, begx) };
-        if new_window.is_null() {
-            Err(ERR)
-        } else {
-            Ok(Window {
-                _window: new_window,
-                _stdscr: false,
-            })
-        }
+    ///
+    /// The subwindow borrows this window, so this window can't be dropped before it.
+    pub fn subwin(&self, nlines: i32, ncols: i32, begy: i32, begx: i32) -> Result<SubWindow<'_>> {
+        let new_window = check_ptr("subwin", unsafe {
+            curses::subwin(self._window, nlines, ncols, begy, begx)
+        })?;
+        let window = self::new_window(new_window, false);
+        window._control_chars.set(self._control_chars.get());
+        Ok(SubWindow {
+            window,
+            parent: self,
+        })
     }
 
     /// Set blocking or non-blocking reads for the specified window.
@@ -542,17 +967,23 @@ impl Window {
     /// If the delay is positive, the read blocks for the delay period; if the period expires,
     /// ERR is returned.
     pub fn timeout(&self, milliseconds: i32) {
+        self._timeout.set(milliseconds);
         unsafe { curses::wtimeout(self._window, milliseconds) }
     }
 
+    /// The delay set with timeout() or nodelay().
+    pub(crate) fn get_timeout(&self) -> i32 {
+        self._timeout.get()
+    }
+
     /// Throws away all information about which parts of the window have been touched, pretending
     /// that the entire window has been drawn on.
     ///
     ///  This is sometimes necessary when using overlapping windows, since a change to one window
     /// will affect the other window, but the records of which lines have been changed in the other
     /// window will not reflect the change.
-    pub fn touch(&self) -> i32 {
-        unsafe { curses::touchwin(self._window) }
+    pub fn touch(&self) -> Result<()> {
+        check("touchwin", unsafe { curses::touchwin(self._window) })
     }
 
     /// Throws away all information about which parts of the window have been touched, pretending
@@ -561,41 +992,328 @@ impl Window {
     ///  This is sometimes necessary when using overlapping windows, since a change to one window
     /// will affect the other window, but the records of which lines have been changed in the other
     /// window will not reflect the change.
-    pub fn touchline(&self, start: i32, count: i32) -> i32 {
-        unsafe { curses::touchline(self._window, start, count) }
+    pub fn touchline(&self, start: i32, count: i32) -> Result<()> {
+        check("touchline", unsafe {
+            curses::touchline(self._window, start, count)
+        })
     }
 
     /// Makes n lines in the window, starting at line y, look as if they have or have not been
     /// changed since the last call to refresh().
-    pub fn touchln(&self, y: i32, n: i32, changed: bool) -> i32 {
-        unsafe { curses::wtouchln(self._window, y, n, if changed { 1 } else { 0 }) }
+    pub fn touchln(&self, y: i32, n: i32, changed: bool) -> Result<()> {
+        check("wtouchln", unsafe {
+            curses::wtouchln(self._window, y, n, if changed { 1 } else { 0 })
+        })
     }
 
     /// Places ch back onto the input queue to be returned by the next call to getch().
-    pub fn ungetch(&self, input: &Input) -> i32 {
-        platform_specific::_ungetch(input)
+    ///
+    /// Keys with the Alt modifier that curses has no code for are put back as an escape character
+    /// followed by the key, the way terminals send them.
+    pub fn ungetch(&self, input: &Input) -> Result<()> {
+        if *input == Input::Wakeup {
+            return unget_wakeup();
+        }
+        #[cfg(unix)]
+        waker::set_pending();
+        // Input that getch() decodes from control sequences goes back as the sequence
+        let sequence = match *input {
+            Input::Paste(ref text) => Some(format!("{}{}{}", PASTE_START, text, PASTE_END)),
+            Input::FocusGained => Some("\x1b[I".to_string()),
+            Input::FocusLost => Some("\x1b[O".to_string()),
+            Input::Mouse(event) => event.to_sgr(),
+            _ => None,
+        };
+        if let (Input::Mouse(event), None) = (input, &sequence) {
+            return check(
+                "ungetmouse",
+                platform_specific::_ungetmouse(&event.to_mevent()),
+            );
+        }
+        if let Some(sequence) = sequence {
+            for c in sequence.chars().rev() {
+                check("ungetch", platform_specific::_ungetch(&Input::Character(c)))?;
+            }
+            return Ok(());
+        }
+        let result = platform_specific::_ungetch(input);
+        match input.without_alt() {
+            Some(unprefixed) if result == ERR => {
+                // ungetch() works like a stack, so the escape character goes in last
+                self.ungetch(&unprefixed)?;
+                check("ungetch", platform_specific::_ungetch(&Input::KeyEscape))
+            }
+            _ => check("ungetch", result),
+        }
     }
 
     /// Marks all lines in the window as unchanged since the last call to refresh().
-    pub fn untouch(&self) -> i32 {
-        unsafe { curses::untouchwin(self._window) }
+    pub fn untouch(&self) -> Result<()> {
+        check("untouchwin", unsafe { curses::untouchwin(self._window) })
     }
 
     /// Draw a vertical line using ch from the current cursor position. The line is at most
     /// n characters long, or as many as fit into the window.
-    pub fn vline<T: ToChtype>(&self, ch: T, n: i32) -> i32 {
-        unsafe { curses::wvline(self._window, ch.to_chtype(), n) }
+    pub fn vline<T: ToChtype>(&self, ch: T, n: i32) -> Result<()> {
+        check("wvline", unsafe {
+            curses::wvline(self._window, ch.to_chtype(), n)
+        })
+    }
+
+    /// Returns what the string output functions do with control characters.
+    pub fn control_chars(&self) -> ControlChars {
+        self._control_chars.get()
+    }
+
+    /// Sets what the string output functions (addstr(), addnstr(), addstr_cols(), mvaddstr(),
+    /// mvaddnstr(), mvaddstr_cols(), printw() and mvprintw()) do with NUL bytes and other control
+    /// characters. Windows created with subwin(), derwin() and dupwin() start out with the setting
+    /// of this window.
+    pub fn set_control_chars(&self, control_chars: ControlChars) {
+        self._control_chars.set(control_chars)
+    }
+
+    /// Converts a string for output according to the control character setting of the window.
+    fn to_output_cstring(&self, function: &'static str, string: &str) -> Result<CString> {
+        to_cstring(function, &self.apply_control_chars(function, string)?)
+    }
+
+    /// Applies the control character setting of the window to a string that is to be output.
+    fn apply_control_chars<'a>(
+        &self,
+        function: &'static str,
+        string: &'a str,
+    ) -> Result<Cow<'a, str>> {
+        fn is_filtered(c: char) -> bool {
+            c.is_control() && c != '\n' && c != '\t'
+        }
+
+        match self._control_chars.get() {
+            ControlChars::Passthrough => Ok(Cow::Borrowed(string)),
+            ControlChars::Reject => match string.char_indices().find(|&(_, c)| is_filtered(c)) {
+                Some((position, character)) => Err(Error::ControlCharacter {
+                    function,
+                    position,
+                    character,
+                }),
+                None => Ok(Cow::Borrowed(string)),
+            },
+            ControlChars::Replace(replacement) => Ok(Cow::Owned(
+                string
+                    .chars()
+                    .map(|c| if is_filtered(c) { replacement } else { c })
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Prepares a string for addstr_cols() and mvaddstr_cols(), which output it from the given
+    /// column on. Returns the string as it will be output, truncated to max_cols columns, and the
+    /// number of columns it takes up.
+    fn fit_cols(
+        &self,
+        function: &'static str,
+        string: &str,
+        column: i32,
+        max_cols: usize,
+        ellipsis: Option<&str>,
+    ) -> Result<(String, usize)> {
+        let string = self.apply_control_chars(function, string)?;
+        let ellipsis = match ellipsis {
+            Some(ellipsis) => Some(self.apply_control_chars(function, ellipsis)?),
+            None => None,
+        };
+        let tab_size = platform_specific::_TABSIZE() as usize;
+        let string = expand_tabs(&string, column.max(0) as usize, tab_size);
+        let (s, width) = truncate(&string, max_cols, ellipsis.as_deref());
+        Ok((s.into_owned(), width))
+    }
+
+    /// The curses window pointer, for the types that wrap a Window.
+    pub(crate) fn as_ptr(&self) -> WindowPointer {
+        self._window
+    }
+
+    /// Like `check()`, but reports a failure as `Error::OutOfBounds` if the position the cursor
+    /// was supposed to be moved to is outside of the window.
+    fn check_move(&self, function: &'static str, y: i32, x: i32, code: i32) -> Result<()> {
+        if code != ERR {
+            Ok(())
+        } else {
+            Err(self.move_error(function, y, x))
+        }
+    }
+
+    /// The error for a failed call that first moved the cursor to (y, x).
+    fn move_error(&self, function: &'static str, y: i32, x: i32) -> Error {
+        if y < 0 || x < 0 || y >= self.get_max_y() || x >= self.get_max_x() {
+            Error::OutOfBounds { function, y, x }
+        } else {
+            Error::Curses { function }
+        }
+    }
+}
+
+/// A window created within another window with subwin() or derwin().
+///
+/// A subwindow shares the characters of its parent, so the parent must outlive it. This is
+/// enforced by the subwindow borrowing its parent:
+///
+/// ```compile_fail
+/// use pancurses::{endwin, initscr, newwin};
+///
+/// let window = initscr();
+/// let parent = newwin(10, 10, 0, 0).unwrap();
+/// let child = parent.derwin(5, 5, 1, 1).unwrap();
+/// drop(parent);
+/// child.addstr("Use after free").unwrap();
+/// endwin();
+/// ```
+///
+/// A subwindow dereferences to a `Window`, so it has all the same methods. In addition it can be
+/// moved within its parent. It only gives out shared references to the window, so the window
+/// can't be taken out of the subwindow to outlive its parent either:
+///
+/// ```compile_fail
+/// use pancurses::{endwin, initscr, newwin};
+/// use std::mem;
+///
+/// let window = initscr();
+/// let parent = newwin(10, 10, 0, 0).unwrap();
+/// let mut child = parent.derwin(5, 5, 1, 1).unwrap();
+/// let escaped = mem::replace(&mut *child, newwin(1, 1, 0, 0).unwrap());
+/// endwin();
+/// ```
+#[derive(Debug)]
+pub struct SubWindow<'parent> {
+    window: Window,
+    parent: &'parent Window,
+}
+
+impl<'parent> SubWindow<'parent> {
+    /// Deletes the subwindow, freeing all associated memory.
+    pub fn delwin(self) -> Result<()> {
+        self.window.delwin()
+    }
+
+    /// Get the y coordinate of the upper-left corner of this window relative to its parent.
+    pub fn get_par_y(&self) -> i32 {
+        unsafe { curses::getpary(self.window._window) }
+    }
+
+    /// Get the x coordinate of the upper-left corner of this window relative to its parent.
+    pub fn get_par_x(&self) -> i32 {
+        unsafe { curses::getparx(self.window._window) }
+    }
+
+    /// Get the y and x coordinates of the upper-left corner of this window relative to its
+    /// parent.
+    ///
+    /// ```rust
+    /// use pancurses::{endwin, initscr};
+    ///
+    /// let window = initscr();
+    /// let child = window.derwin(5, 5, 1, 2).unwrap();
+    /// assert_eq!((1, 2), child.get_par_yx());
+    /// child.mvderwin(3, 4).unwrap();
+    /// assert_eq!((3, 4), child.get_par_yx());
+    /// endwin();
+    /// ```
+    pub fn get_par_yx(&self) -> (i32, i32) {
+        (self.get_par_y(), self.get_par_x())
+    }
+
+    /// Moves the subwindow inside its parent window.
+    ///
+    /// The screen-relative parameters of the window are not changed. This routine is used to
+    /// display different parts of the parent window at the same physical position on the screen.
+    pub fn mvderwin(&self, pary: i32, parx: i32) -> Result<()> {
+        check("mvderwin", unsafe {
+            curses::mvderwin(self.window._window, pary, parx)
+        })
+    }
+
+    /// The window this subwindow was created in.
+    pub fn parent(&self) -> &'parent Window {
+        self.parent
+    }
+}
+
+impl AsRef<Window> for Window {
+    fn as_ref(&self) -> &Window {
+        self
+    }
+}
+
+impl<'parent> AsRef<Window> for SubWindow<'parent> {
+    fn as_ref(&self) -> &Window {
+        &self.window
     }
 }
 
+impl<'parent> Deref for SubWindow<'parent> {
+    type Target = Window;
+
+    fn deref(&self) -> &Window {
+        &self.window
+    }
+}
+
+/// Reads the input that follows an escape character from a window.
+struct WindowSource<'a>(&'a Window, fn(WindowPointer) -> Option<Input>);
+
+impl<'a> escape::Source for WindowSource<'a> {
+    fn read(&mut self) -> Option<Input> {
+        (self.1)(self.0._window)
+    }
+
+    fn read_blocking(&mut self) -> Option<Input> {
+        unsafe { curses::wtimeout(self.0._window, -1) };
+        (self.1)(self.0._window)
+    }
+
+    fn unread(&mut self, input: &Input) {
+        #[cfg(unix)]
+        waker::set_pending();
+        platform_specific::_ungetch(input);
+    }
+}
+
+/// Reads the mouse event curses reported with KeyMouse.
+fn decode_mouse(input: Option<Input>) -> Option<Input> {
+    match input {
+        Some(Input::KeyMouse) => match platform_specific::_getmouse() {
+            Ok(event) => Some(Input::Mouse(MouseEvent::from_mevent(&event))),
+            Err(_) => input,
+        },
+        _ => input,
+    }
+}
+
+/// Makes the next getch() return `Input::Wakeup`, for ungetch().
+#[cfg(unix)]
+fn unget_wakeup() -> Result<()> {
+    waker::Waker::new()?.wake()
+}
+
+#[cfg(windows)]
+fn unget_wakeup() -> Result<()> {
+    Err(Error::Curses {
+        function: "ungetch",
+    })
+}
+
 pub fn new_window(window_pointer: WindowPointer, is_stdscr: bool) -> Window {
     Window {
         _window: window_pointer,
         _stdscr: is_stdscr,
+        _screen: SCREEN.with(|screen| screen.borrow().clone()),
+        _control_chars: Cell::default(),
+        _timeout: Cell::new(-1),
     }
 }
 
-/// Automatically clean up window resources when dropped
+/// Automatically clean up window resources when dropped, before the screen is released
 impl Drop for Window {
     fn drop(&mut self) {
         if !self._stdscr {
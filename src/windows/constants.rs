@@ -1,4 +1,4 @@
-use input::Input;
+use input::{Input, Key, Modifiers};
 use pdcurses::chtype;
 use pdcurses::mmask_t;
 
@@ -57,6 +57,37 @@ pub const KEY_NUMPAD_MINUS: i32 = 60624;
 pub const KEY_NUMPAD_ASTERISK: i32 = 60623;
 pub const KEY_NUMPAD_SLASH: i32 = 60618;
 
+pub const KEY_F0: i32 = KEY_OFFSET + 0x08;
+pub const KEY_ALT_0: i32 = KEY_OFFSET + 0x97;
+pub const KEY_ALT_9: i32 = KEY_OFFSET + 0xa0;
+pub const KEY_ALT_A: i32 = KEY_OFFSET + 0xa1;
+pub const KEY_ALT_Z: i32 = KEY_OFFSET + 0xba;
+
+/// The CTL_, ALT_ and KEY_S codes PDCurses uses for keys pressed with modifiers.
+pub const MODIFIED_KEY_CODES: [(i32, Key, Modifiers); 21] = [
+    (KEY_OFFSET + 0xbb, Key::Left, Modifiers::CTRL),
+    (KEY_OFFSET + 0xbc, Key::Right, Modifiers::CTRL),
+    (KEY_OFFSET + 0xbd, Key::PageUp, Modifiers::CTRL),
+    (KEY_OFFSET + 0xbe, Key::PageDown, Modifiers::CTRL),
+    (KEY_OFFSET + 0xbf, Key::Home, Modifiers::CTRL),
+    (KEY_OFFSET + 0xc0, Key::End, Modifiers::CTRL),
+    (KEY_OFFSET + 0xdd, Key::Insert, Modifiers::CTRL),
+    (KEY_OFFSET + 0xde, Key::Delete, Modifiers::ALT),
+    (KEY_OFFSET + 0xdf, Key::Insert, Modifiers::ALT),
+    (KEY_OFFSET + 0xe0, Key::Up, Modifiers::CTRL),
+    (KEY_OFFSET + 0xe1, Key::Down, Modifiers::CTRL),
+    (KEY_OFFSET + 0xe6, Key::Home, Modifiers::ALT),
+    (KEY_OFFSET + 0xe7, Key::PageUp, Modifiers::ALT),
+    (KEY_OFFSET + 0xe8, Key::PageDown, Modifiers::ALT),
+    (KEY_OFFSET + 0xe9, Key::End, Modifiers::ALT),
+    (KEY_OFFSET + 0xea, Key::Up, Modifiers::ALT),
+    (KEY_OFFSET + 0xeb, Key::Down, Modifiers::ALT),
+    (KEY_OFFSET + 0xec, Key::Right, Modifiers::ALT),
+    (KEY_OFFSET + 0xed, Key::Left, Modifiers::ALT),
+    (KEY_OFFSET + 0x123, Key::Up, Modifiers::SHIFT),
+    (KEY_OFFSET + 0x124, Key::Down, Modifiers::SHIFT),
+];
+
 pub const SPECIAL_KEY_CODES: [Input; 102] = [
     Input::KeyCodeYes,
     Input::KeyBreak,
@@ -6,11 +6,14 @@ use std::ffi::{CStr, CString};
 use std::char::decode_utf16;
 use std::iter;
 use std::cmp;
+use std::ptr;
+use std::sync::atomic::{AtomicI32, Ordering};
 
 pub mod constants;
 use self::constants::*;
 
-use input::Input;
+use input::{Input, Key, Modifiers};
+use {ComplexChar, Error, ERR};
 
 #[cfg(any(feature = "win32a", all(not(feature = "win32"), not(feature = "win32a"))))]
 mod win32a;
@@ -24,6 +27,36 @@ use self::win32 as flavor;
 
 pub use self::flavor::pre_init;
 
+/// The screen created by newterm(), which in PDCurses is always SP.
+#[derive(Debug)]
+pub struct Screen {
+    screen: *mut SCREEN,
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        unsafe { delscreen(self.screen) }
+    }
+}
+
+/// PDCurses doesn't depend on a terminal type or a tty, so initialization can only fail if
+/// PDCurses itself fails.
+pub fn _try_newterm() -> crate::Result<Screen> {
+    pre_init();
+    let screen = unsafe { newterm(ptr::null(), ptr::null_mut(), ptr::null_mut()) };
+    if screen.is_null() {
+        Err(Error::NullPointer {
+            function: "newterm",
+        })
+    } else {
+        Ok(Screen { screen })
+    }
+}
+
+pub fn _stdscr() -> *mut WINDOW {
+    unsafe { stdscr }
+}
+
 pub fn _attron(w: *mut WINDOW, attributes: chtype) -> i32 {
     unsafe { wattron(w, attributes) }
 }
@@ -40,6 +73,10 @@ pub fn _COLORS() -> i32 {
     unsafe { COLORS }
 }
 
+pub fn _TABSIZE() -> i32 {
+    unsafe { TABSIZE }
+}
+
 pub fn _COLOR_PAIRS() -> i32 {
     unsafe { COLOR_PAIRS }
 }
@@ -64,6 +101,15 @@ pub fn _getmouse() -> Result<MEVENT, i32> {
     }
 }
 
+pub fn _ungetmouse(event: &MEVENT) -> i32 {
+    let mut event = *event;
+    unsafe { ungetmouse(&mut event) }
+}
+
+pub fn _set_mouse_decoding(_: bool) -> i32 {
+    0 // The mouse doesn't come in as escape sequences
+}
+
 pub fn _keyname(code: i32) -> Option<String> {
     let ptr = unsafe { keyname(code) };
     if ptr.is_null() {
@@ -82,6 +128,27 @@ pub fn _resize_term(nlines: i32, ncols: i32) -> i32 {
     unsafe { resize_term(nlines, ncols) }
 }
 
+/// PDCurses has no escape delay of its own, so this only affects how long getch() waits after an
+/// escape character.
+static ESCAPE_DELAY: AtomicI32 = AtomicI32::new(0);
+
+pub fn _get_escdelay() -> i32 {
+    ESCAPE_DELAY.load(Ordering::Relaxed)
+}
+
+pub fn _set_escdelay(milliseconds: i32) -> i32 {
+    ESCAPE_DELAY.store(milliseconds, Ordering::Relaxed);
+    0
+}
+
+pub fn _escape_wait(_: *mut WINDOW) -> i32 {
+    _get_escdelay()
+}
+
+pub fn _write_terminal(_: &str) -> i32 {
+    crate::ERR // Not supported
+}
+
 pub fn _set_blink(enabled: bool) -> i32 {
     unsafe { PDC_set_blink(enabled as u8) }
 }
@@ -91,6 +158,45 @@ pub fn _set_title(title: &str) {
     unsafe { PDC_set_title(s.as_ptr()) }
 }
 
+/// The highest function key PDCurses reports for a key with modifiers.
+const MAX_FUNCTION_KEY: i32 = 48;
+
+/// PDCurses reports F1 to F12 with Shift as F13 to F24, with Ctrl as F25 to F36 and with Alt as
+/// F37 to F48.
+const FUNCTION_KEY_MODIFIERS: [Modifiers; 4] = [
+    Modifiers::NONE,
+    Modifiers::SHIFT,
+    Modifiers::CTRL,
+    Modifiers::ALT,
+];
+
+/// Finds the key code PDCurses uses for a key with modifiers.
+fn modified_key_code(key: Key, modifiers: Modifiers) -> Option<i32> {
+    match key {
+        Key::F(n) if (1..=12).contains(&n) => {
+            let group = FUNCTION_KEY_MODIFIERS
+                .iter()
+                .position(|&m| m == modifiers)? as i32;
+            let code = KEY_F0 + group * 12 + n as i32;
+            // F13 to F15 are reported as Input::KeyF13 to Input::KeyF15
+            if code - KEY_F0 > 15 {
+                Some(code)
+            } else {
+                None
+            }
+        }
+        Key::Character(c) if modifiers == Modifiers::ALT => match c {
+            '0'..='9' => Some(KEY_ALT_0 + (c as i32 - '0' as i32)),
+            'a'..='z' => Some(KEY_ALT_A + (c as i32 - 'a' as i32)),
+            _ => None,
+        },
+        _ => MODIFIED_KEY_CODES
+            .iter()
+            .find(|m| m.1 == key && m.2 == modifiers)
+            .map(|m| m.0),
+    }
+}
+
 /// Converts an integer returned by getch() to an Input value
 pub fn to_special_keycode(i: i32) -> Option<Input> {
     // There's two sets of integer constants defined:
@@ -118,7 +224,27 @@ pub fn to_special_keycode(i: i32) -> Option<Input> {
         KEY_NUMPAD_ASTERISK => Some(Input::Character('*')),
         KEY_NUMPAD_SLASH => Some(Input::Character('/')),
 
+        _ if (16..=MAX_FUNCTION_KEY).contains(&(i - KEY_F0)) => {
+            let n = i - KEY_F0 - 1;
+            Some(Input::KeyModified(
+                Key::F((n % 12 + 1) as u8),
+                FUNCTION_KEY_MODIFIERS[(n / 12) as usize],
+            ))
+        }
+
+        KEY_ALT_0..=KEY_ALT_9 => Some(Input::KeyModified(
+            Key::Character((b'0' + (i - KEY_ALT_0) as u8) as char),
+            Modifiers::ALT,
+        )),
+        KEY_ALT_A..=KEY_ALT_Z => Some(Input::KeyModified(
+            Key::Character((b'a' + (i - KEY_ALT_A) as u8) as char),
+            Modifiers::ALT,
+        )),
+
         _ => {
+            if let Some(&(_, key, modifiers)) = MODIFIED_KEY_CODES.iter().find(|m| m.0 == i) {
+                return Some(Input::KeyModified(key, modifiers));
+            }
             // Since not all special key codes have been added to the SPECIAL_KEY_CODES array,
             // we need to do some basic math if this input lands into it.
             let index = if i <= KEY_F15 {
@@ -132,7 +258,7 @@ pub fn to_special_keycode(i: i32) -> Option<Input> {
                 // Input is something else. This may require more processing to convert properly into utf8
                 None
             } else {
-                Some(SPECIAL_KEY_CODES[index as usize])
+                Some(SPECIAL_KEY_CODES[index as usize].clone())
             }
         }
     }
@@ -174,6 +300,55 @@ pub fn _wgetch(w: *mut WINDOW) -> Option<Input> {
     }
 }
 
+/// In a wide PDCurses build cchar_t is the same as chtype and holds a single character, so any
+/// combining characters are dropped.
+fn to_chtype(wch: &ComplexChar) -> chtype {
+    let c = wch.text().chars().next().unwrap_or(' ');
+    c as chtype | (wch.attributes() & !A_COLOR) | COLOR_PAIR(wch.color_pair() as chtype)
+}
+
+fn from_chtype(ch: chtype) -> Result<ComplexChar, i32> {
+    if ch == ERR as chtype {
+        return Err(ERR);
+    }
+    let c = ::std::char::from_u32((ch & A_CHARTEXT) as u32).unwrap_or(' ');
+    ComplexChar::new(
+        c.to_string(),
+        ch & !(A_CHARTEXT | A_COLOR),
+        ((ch & A_COLOR) >> PDC_COLOR_SHIFT) as i16,
+    )
+    .map_err(|_| ERR)
+}
+
+pub fn _wadd_wch(w: *mut WINDOW, wch: &ComplexChar) -> i32 {
+    unsafe { waddch(w, to_chtype(wch)) }
+}
+
+pub fn _mvwadd_wch(w: *mut WINDOW, y: i32, x: i32, wch: &ComplexChar) -> i32 {
+    unsafe { mvwaddch(w, y, x, to_chtype(wch)) }
+}
+
+pub fn _win_wch(w: *mut WINDOW) -> Result<ComplexChar, i32> {
+    from_chtype(unsafe { winch(w) })
+}
+
+pub fn _mvwin_wch(w: *mut WINDOW, y: i32, x: i32) -> Result<ComplexChar, i32> {
+    from_chtype(unsafe { mvwinch(w, y, x) })
+}
+
+pub fn _wins_wch(w: *mut WINDOW, wch: &ComplexChar) -> i32 {
+    unsafe { winsch(w, to_chtype(wch)) }
+}
+
+pub fn _mvwins_wch(w: *mut WINDOW, y: i32, x: i32, wch: &ComplexChar) -> i32 {
+    unsafe { mvwinsch(w, y, x, to_chtype(wch)) }
+}
+
+/// PDCurses always reads wide characters, so this is the same as wgetch().
+pub fn _wget_wch(w: *mut WINDOW) -> Option<Input> {
+    _wgetch(w)
+}
+
 pub fn _ungetch(input: &Input) -> i32 {
     match *input {
         Input::Character(c) => {
@@ -188,9 +363,14 @@ pub fn _ungetch(input: &Input) -> i32 {
         Input::Unknown(i) => unsafe { PDC_ungetch(i) },
         Input::KeyResize => unsafe { PDC_ungetch(KEY_RESIZE) },
         Input::KeyMouse => unsafe { PDC_ungetch(KEY_MOUSE) },
-        specialKeyCode => {
+        Input::KeyEscape => unsafe { PDC_ungetch(0x1b) },
+        Input::KeyModified(key, modifiers) => match modified_key_code(key, modifiers) {
+            Some(code) => unsafe { PDC_ungetch(code) },
+            None => ERR,
+        },
+        ref specialKeyCode => {
             for (i, skc) in SPECIAL_KEY_CODES.iter().enumerate() {
-                if *skc == specialKeyCode {
+                if skc == specialKeyCode {
                     let result = i as c_int + KEY_OFFSET;
                     if result <= KEY_F15 {
                         return unsafe { PDC_ungetch(result) };
@@ -227,6 +407,32 @@ mod tests {
         assert_eq!(Input::KeyUp, to_special_keycode(KEY_OFFSET + 3).unwrap());
     }
 
+    #[test]
+    fn test_modified_key_to_input() {
+        assert_eq!(
+            Some(Input::KeyModified(Key::Left, Modifiers::CTRL)),
+            to_special_keycode(KEY_OFFSET + 0xbb)
+        );
+        assert_eq!(
+            Some(Input::KeyModified(Key::F(5), Modifiers::CTRL)),
+            to_special_keycode(KEY_F0 + 29)
+        );
+        assert_eq!(
+            Some(KEY_F0 + 41),
+            modified_key_code(Key::F(5), Modifiers::ALT)
+        );
+    }
+
+    #[test]
+    fn test_alt_key_to_input() {
+        let alt_q = Input::KeyModified(Key::Character('q'), Modifiers::ALT);
+        assert_eq!(Some(alt_q), to_special_keycode(KEY_ALT_A + 16));
+        assert_eq!(
+            Some(KEY_ALT_0 + 7),
+            modified_key_code(Key::Character('7'), Modifiers::ALT)
+        );
+    }
+
     #[test]
     fn test_ungetch() {
         let w = unsafe { initscr() };